@@ -21,9 +21,22 @@ impl DataType {
             "str" => DataType::Primitive(PrimitiveDataType::Str),
             "null" => DataType::Primitive(PrimitiveDataType::Null),
             "bool" => DataType::Primitive(PrimitiveDataType::Bool),
+            "indefinite" => DataType::Complex(ComplexDataType::Indefinite),
             "iterable" => DataType::Primitive(PrimitiveDataType::Iterable(Box::new(
                 generics[0].clone(),
             ))),
+            "result" => {
+                let generic = |index: usize| {
+                    Box::new(
+                        generics
+                            .get(index)
+                            .cloned()
+                            .unwrap_or(DataType::Complex(ComplexDataType::Indefinite)),
+                    )
+                };
+
+                DataType::Primitive(PrimitiveDataType::Result(generic(0), generic(1)))
+            }
             v => DataType::Complex(ComplexDataType::LayoutOrEnum(v.to_string())),
         }
     }
@@ -44,6 +57,7 @@ impl Display for DataType {
             DataType::Primitive(primitive) => match primitive {
                 PrimitiveDataType::Num(_) => "num",
                 PrimitiveDataType::Iterable(g) => &format!("iterable[{}]", g.to_string()),
+                PrimitiveDataType::Result(ok, err) => &format!("result[{}, {}]", ok, err),
                 PrimitiveDataType::Str => "str",
                 PrimitiveDataType::Bool => "bool",
                 PrimitiveDataType::Nullable(v) => &format!("nul {}", (&*v).clone()),
@@ -67,6 +81,8 @@ impl Display for DataType {
 pub enum PrimitiveDataType {
     Num(NumType),
     Iterable(Box<DataType>),
+    /// `result[T, E]`, a value only knows the type of its own side, the other one is `indefinite`
+    Result(Box<DataType>, Box<DataType>),
     Str,
     Bool,
     Nullable(Box<DataType>),
@@ -0,0 +1,328 @@
+use crate::err;
+use crate::global::{ComplexDataType, DataType, PrimitiveDataType};
+use crate::interpreter::scope::RuntimeScopeW;
+use crate::interpreter::structs::{
+    ComplexRuntimeValue, EnumData, IterablePair, LayoutData, RuntimeValue,
+};
+use crate::interpreter::Interpreter;
+use crate::json;
+use crate::json::JsonValue;
+use crate::parser::structs::LayoutCreation;
+use indexmap::IndexMap;
+use std::collections::{HashMap, HashSet};
+use std::process::exit;
+use std::sync::Arc;
+
+impl Interpreter {
+    pub(super) fn eval_derived_function(
+        &self,
+        layout_id: &String,
+        function: &str,
+        scope: RuntimeScopeW,
+    ) -> RuntimeValue {
+        match function {
+            "default" => self.eval_layout_creation(
+                LayoutCreation {
+                    name: layout_id.clone(),
+                    specified_fields: HashMap::new(),
+                },
+                scope,
+            ),
+            "toStr" => {
+                match self.get_identifier_value("self".to_string(), scope) {
+                    RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) => {
+                        RuntimeValue::String(v.render_default())
+                    }
+                    v => RuntimeValue::String(v.to_string()),
+                }
+            }
+            "eq" => {
+                let this = self.get_identifier_value("self".to_string(), scope.clone());
+                let other = self.get_identifier_value("other".to_string(), scope);
+                RuntimeValue::Bool(structural_eq(&this, &other))
+            }
+            "clone" => {
+                let this = self.get_identifier_value("self".to_string(), scope);
+                deep_copy(&this)
+            }
+            "toJson" => {
+                let this = self.get_identifier_value("self".to_string(), scope);
+                match value_to_json(&this) {
+                    Ok(v) => RuntimeValue::String(v.to_json_string(false)),
+                    Err(e) => {
+                        err!(intrp "Cannot convert `{}` to JSON: {}", layout_id, e);
+                        exit(100)
+                    }
+                }
+            }
+            "fromJson" => {
+                let text = self.get_identifier_value("json".to_string(), scope.clone());
+                let parsed = json::parse(text.cast_string().unwrap());
+
+                let value = parsed.map_err(|e| e.to_string()).and_then(|v| {
+                    self.json_to_value(
+                        &v,
+                        &DataType::Complex(ComplexDataType::LayoutOrEnum(layout_id.clone())),
+                        scope,
+                    )
+                });
+
+                match value {
+                    Ok(v) => v,
+                    Err(e) => {
+                        err!(intrp "Cannot create `{}` from JSON: {}", layout_id, e);
+                        exit(100)
+                    }
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Converts JSON into a value of the given type, using field defaults for missing fields.
+    pub(super) fn json_to_value(
+        &self,
+        value: &JsonValue,
+        data_type: &DataType,
+        scope: RuntimeScopeW,
+    ) -> Result<RuntimeValue, String> {
+        let mismatch = || Err(format!("Expected a value of type `{}`", data_type));
+
+        match data_type {
+            DataType::Primitive(primitive) => match (primitive, value) {
+                (PrimitiveDataType::Nullable(_), JsonValue::Null) => Ok(RuntimeValue::Null),
+                (PrimitiveDataType::Nullable(inner), v) => self.json_to_value(v, inner, scope),
+                (PrimitiveDataType::Null, JsonValue::Null) => Ok(RuntimeValue::Null),
+                (PrimitiveDataType::Num(_), JsonValue::Number(v)) => Ok(RuntimeValue::Number(*v)),
+                (PrimitiveDataType::Str, JsonValue::String(v)) => Ok(RuntimeValue::String(v.clone())),
+                (PrimitiveDataType::Bool, JsonValue::Bool(v)) => Ok(RuntimeValue::Bool(*v)),
+                (PrimitiveDataType::Iterable(inner), JsonValue::Array(v)) => {
+                    let mut res = vec![];
+                    for (index, item) in v.iter().enumerate() {
+                        res.push(IterablePair {
+                            index,
+                            value: self.json_to_value(item, inner, scope.clone())?,
+                        });
+                    }
+                    Ok(RuntimeValue::Iterable(res))
+                }
+                _ => mismatch(),
+            },
+            DataType::Complex(ComplexDataType::LayoutOrEnum(name)) => {
+                if let Some(enum_data) = scope.read().unwrap().get_enum_data(name) {
+                    return match value {
+                        JsonValue::String(entry) if enum_data.entries.contains(entry) => {
+                            Ok(RuntimeValue::Complex(ComplexRuntimeValue::Enum(EnumData {
+                                enum_id: name.clone(),
+                                entry: entry.clone(),
+                            })))
+                        }
+                        _ => Err(format!("Expected an entry of enum `{}`", name)),
+                    };
+                }
+
+                let Some(decl) = scope.read().unwrap().get_layout_declaration(name) else {
+                    return Err(format!("Type `{}` does not exist", name));
+                };
+
+                let JsonValue::Object(object) = value else {
+                    return mismatch();
+                };
+
+                let mut fields = IndexMap::new();
+
+                for (field, description) in &decl.fields {
+                    let value = match (object.get(field), &description.default_value) {
+                        (Some(v), _) => self.json_to_value(
+                            v,
+                            &type_from_id(&description.type_id),
+                            scope.clone(),
+                        )?,
+                        (None, Some(default)) => self.eval(default, scope.clone()),
+                        (None, None) => {
+                            return Err(format!("Missing field `{}` of `{}`", field, name))
+                        }
+                    };
+                    fields.insert(field.clone(), value);
+                }
+
+                Ok(LayoutData::new(name.clone(), fields, decl.mixed.clone()).into_value())
+            }
+            DataType::Complex(ComplexDataType::Indefinite) | DataType::InternalInfer => {
+                Ok(json_to_dynamic_value(value))
+            }
+            DataType::Reference(_) => Err("Functions cannot be created from JSON".to_string()),
+        }
+    }
+}
+
+/// Parses a type id stored in a `FieldParserDescription`.
+pub fn type_from_id(type_id: &str) -> DataType {
+    if let Some(inner) = type_id.strip_prefix("nul ") {
+        DataType::Primitive(PrimitiveDataType::Nullable(Box::new(type_from_id(inner))))
+    } else if let Some(inner) = type_id
+        .strip_prefix("iterable[")
+        .and_then(|v| v.strip_suffix(']'))
+    {
+        DataType::Primitive(PrimitiveDataType::Iterable(Box::new(type_from_id(inner))))
+    } else {
+        DataType::from_str(type_id.to_string(), vec![])
+    }
+}
+
+/// Converts JSON without a target type, objects become iterables of their values.
+pub fn json_to_dynamic_value(value: &JsonValue) -> RuntimeValue {
+    match value {
+        JsonValue::Null => RuntimeValue::Null,
+        JsonValue::Bool(v) => RuntimeValue::Bool(*v),
+        JsonValue::Number(v) => RuntimeValue::Number(*v),
+        JsonValue::String(v) => RuntimeValue::String(v.clone()),
+        JsonValue::Array(v) => RuntimeValue::Iterable(
+            v.iter()
+                .enumerate()
+                .map(|(index, item)| IterablePair {
+                    index,
+                    value: json_to_dynamic_value(item),
+                })
+                .collect(),
+        ),
+        JsonValue::Object(v) => RuntimeValue::Iterable(
+            v.values()
+                .enumerate()
+                .map(|(index, item)| IterablePair {
+                    index,
+                    value: json_to_dynamic_value(item),
+                })
+                .collect(),
+        ),
+    }
+}
+
+pub fn value_to_json(value: &RuntimeValue) -> Result<JsonValue, String> {
+    Ok(match value {
+        RuntimeValue::Number(v) => JsonValue::Number(*v),
+        RuntimeValue::Null => JsonValue::Null,
+        RuntimeValue::String(v) => JsonValue::String(v.clone()),
+        RuntimeValue::Bool(v) => JsonValue::Bool(*v),
+        RuntimeValue::Iterable(v) => JsonValue::Array(
+            v.iter()
+                .map(|pair| value_to_json(&pair.value))
+                .collect::<Result<_, _>>()?,
+        ),
+        RuntimeValue::Range(v) => {
+            if !v.end.is_finite() {
+                return Err("infinite ranges cannot be converted".to_string());
+            }
+
+            JsonValue::Array(v.values().map(JsonValue::Number).collect())
+        }
+        RuntimeValue::Result(_) => return Err("results cannot be converted, unwrap them first".to_string()),
+        RuntimeValue::Generator(_) => return Err("generators cannot be converted, collect them first".to_string()),
+        RuntimeValue::Complex(ComplexRuntimeValue::Enum(v)) => JsonValue::String(v.entry.clone()),
+        RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) => {
+            let mut object = IndexMap::new();
+            for (field, item) in v.entries.read().unwrap().iter() {
+                if v.is_private(field) {
+                    continue;
+                }
+
+                object.insert(field.clone(), value_to_json(item)?);
+            }
+            JsonValue::Object(object)
+        }
+        RuntimeValue::Reference(_) => return Err("function references cannot be converted".to_string()),
+    })
+}
+
+pub fn structural_eq(left: &RuntimeValue, right: &RuntimeValue) -> bool {
+    structural_eq_inner(left, right, &mut HashSet::new())
+}
+
+/// `comparing` has the pairs of layouts that are being compared further up, a pair that refers back to
+/// itself is equal as long as the rest of the fields are.
+fn structural_eq_inner(
+    left: &RuntimeValue,
+    right: &RuntimeValue,
+    comparing: &mut HashSet<(*const LayoutData, *const LayoutData)>,
+) -> bool {
+    match (left, right) {
+        (RuntimeValue::Null, RuntimeValue::Null) => true,
+        (RuntimeValue::Iterable(l), RuntimeValue::Iterable(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .zip(r.iter())
+                    .all(|(l, r)| structural_eq_inner(&l.value, &r.value, comparing))
+        }
+        (
+            RuntimeValue::Complex(ComplexRuntimeValue::Layout(l)),
+            RuntimeValue::Complex(ComplexRuntimeValue::Layout(r)),
+        ) => {
+            if Arc::ptr_eq(l, r) || !comparing.insert((Arc::as_ptr(l), Arc::as_ptr(r))) {
+                return true;
+            }
+
+            let l_entries = l.entries.read().unwrap();
+            let r_entries = r.entries.read().unwrap();
+
+            l.layout_id == r.layout_id
+                && l_entries.len() == r_entries.len()
+                && l_entries.iter().all(|(field, lv)| {
+                    r_entries
+                        .get(field)
+                        .is_some_and(|rv| structural_eq_inner(lv, rv, comparing))
+                })
+        }
+        (l, r) => l == r,
+    }
+}
+
+/// Copies layouts and iterables recursively. Class instances are shared, as copying them would
+/// skip their constructors.
+pub fn deep_copy(value: &RuntimeValue) -> RuntimeValue {
+    deep_copy_inner(value, &mut HashMap::new())
+}
+
+/// `copies` has the layouts copied so far, so a layout that refers back to itself refers to its copy.
+fn deep_copy_inner(
+    value: &RuntimeValue,
+    copies: &mut HashMap<*const LayoutData, RuntimeValue>,
+) -> RuntimeValue {
+    match value {
+        RuntimeValue::Iterable(v) => RuntimeValue::Iterable(
+            v.iter()
+                .map(|pair| IterablePair {
+                    index: pair.index,
+                    value: deep_copy_inner(&pair.value, copies),
+                })
+                .collect(),
+        ),
+        RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) if v.private.is_none() => {
+            if let Some(copy) = copies.get(&Arc::as_ptr(v)) {
+                return copy.clone();
+            }
+
+            let copy = LayoutData::new(v.layout_id.clone(), IndexMap::new(), v.methods.clone());
+            let copy_entries = copy.entries.clone();
+            let copy = copy.into_value();
+
+            copies.insert(Arc::as_ptr(v), copy.clone());
+
+            // the entries are read first, the layout may be one of its own fields
+            let entries: Vec<_> = v
+                .entries
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(field, item)| (field.clone(), item.clone()))
+                .collect();
+
+            for (field, item) in entries {
+                let item = deep_copy_inner(&item, copies);
+                copy_entries.write().unwrap().insert(field, item);
+            }
+
+            copy
+        }
+        v => v.clone(),
+    }
+}
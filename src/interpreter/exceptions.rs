@@ -0,0 +1,167 @@
+use crate::err;
+use crate::global::DataType;
+use crate::interpreter::structs::{ComplexRuntimeValue, IterablePair, LayoutData, RuntimeValue};
+use indexmap::IndexMap;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::exit;
+
+/// Unwinds to the closest `try`. The thrown value waits in `THROWN`, as runtime values can't be sent between threads.
+/// A lock held for writing while an error is thrown stays poisoned, so scopes and natives check everything
+/// that can fail before they lock anything for writing.
+struct Thrown;
+
+/// Unwinds to the function `?` was used in, the `Err` it returns waits in `RETURNED`.
+struct Returned;
+
+struct Call {
+    name: String,
+    return_type: DataType,
+}
+
+thread_local! {
+    /// `try` blocks that are running, errors outside of them stop the script
+    static TRY_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static THROWN: RefCell<Option<RuntimeValue>> = const { RefCell::new(None) };
+    static RETURNED: RefCell<Option<RuntimeValue>> = const { RefCell::new(None) };
+    /// the functions being called, the innermost one is last
+    static CALL_STACK: RefCell<Vec<Call>> = const { RefCell::new(vec![]) };
+}
+
+pub fn push_call(name: &str, return_type: &DataType) {
+    let name = if name == "MOSA_INTERNAL_LAMBDA" { "lambda" } else { name };
+
+    CALL_STACK.with(|stack| {
+        stack.borrow_mut().push(Call {
+            name: name.to_string(),
+            return_type: return_type.clone(),
+        })
+    });
+}
+
+pub fn pop_call() {
+    CALL_STACK.with(|stack| stack.borrow_mut().pop());
+}
+
+/// An `Error` of `std:error`, its stack has the called functions, the innermost one first.
+pub fn error_value(message: String) -> RuntimeValue {
+    let stack = CALL_STACK.with(|stack| {
+        stack
+            .borrow()
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, call)| IterablePair {
+                index,
+                value: RuntimeValue::String(call.name.clone()),
+            })
+            .collect()
+    });
+
+    let entries = IndexMap::from([
+        ("message".to_string(), RuntimeValue::String(message)),
+        ("stack".to_string(), RuntimeValue::Iterable(stack)),
+    ]);
+
+    LayoutData::new("Error".to_string(), entries, Default::default()).into_value()
+}
+
+pub fn throw(value: RuntimeValue) -> ! {
+    // without unwinding nothing can be caught, so errors stop the script like outside of `try`
+    if TRY_DEPTH.get() == 0 || cfg!(panic = "abort") {
+        report_uncaught(&value);
+        exit(100)
+    }
+
+    THROWN.set(Some(value));
+    panic::resume_unwind(Box::new(Thrown))
+}
+
+/// Runtime errors go through here, inside of `try` they are thrown as an `Error` instead of stopping the script.
+pub fn throw_if_caught(message: &str) {
+    if TRY_DEPTH.get() > 0 {
+        throw(error_value(message.to_string()))
+    }
+}
+
+/// Runs `f`, and returns what was thrown inside of it. Panics of the interpreter itself are not caught.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Box<RuntimeValue>> {
+    let calls = CALL_STACK.with(|stack| stack.borrow().len());
+
+    TRY_DEPTH.set(TRY_DEPTH.get() + 1);
+    let res = panic::catch_unwind(AssertUnwindSafe(f));
+    TRY_DEPTH.set(TRY_DEPTH.get() - 1);
+
+    match res {
+        Ok(v) => Ok(v),
+        Err(payload) if payload.is::<Thrown>() => {
+            CALL_STACK.with(|stack| stack.borrow_mut().truncate(calls));
+
+            Err(Box::new(THROWN.take().unwrap_or(RuntimeValue::Null)))
+        }
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// The name and the return type of the function being called.
+pub fn current_call() -> Option<(String, DataType)> {
+    CALL_STACK.with(|stack| {
+        stack
+            .borrow()
+            .last()
+            .map(|call| (call.name.clone(), call.return_type.clone()))
+    })
+}
+
+pub fn return_early(value: RuntimeValue) -> ! {
+    if cfg!(panic = "abort") {
+        err!(intrp "`?` cannot return `{}` early, as MoSa was built with `panic = \"abort\"`.", value);
+        exit(100)
+    }
+
+    RETURNED.set(Some(value));
+    panic::resume_unwind(Box::new(Returned))
+}
+
+/// Runs the body of a function, which `?` can end early.
+pub fn returning(f: impl FnOnce() -> RuntimeValue) -> RuntimeValue {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(v) => v,
+        Err(payload) if payload.is::<Returned>() => RETURNED.take().unwrap_or(RuntimeValue::Null),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Runs `f`, and `on_return` too if `?` returns from the function in the middle of it.
+pub fn on_return<T>(f: impl FnOnce() -> T, on_return: impl FnOnce()) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(v) => v,
+        Err(payload) => {
+            if payload.is::<Returned>() {
+                on_return();
+            }
+
+            panic::resume_unwind(payload)
+        }
+    }
+}
+
+fn report_uncaught(value: &RuntimeValue) {
+    if let RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) = value {
+        let entries = v.entries.read().unwrap();
+
+        if let (Some(RuntimeValue::String(message)), Some(RuntimeValue::Iterable(stack))) =
+            (entries.get("message"), entries.get("stack"))
+        {
+            let calls: String = stack
+                .iter()
+                .map(|pair| format!("\n    in `{}`", pair.value))
+                .collect();
+
+            err!(intrp "Uncaught `{}`: {}{}", v.layout_id, message, calls);
+            return;
+        }
+    }
+
+    err!(intrp "Uncaught value `{}` was thrown.", value);
+}
@@ -0,0 +1,241 @@
+use crate::err;
+use crate::global::DataType;
+use crate::interpreter::scope::{RuntimeScope, RuntimeScopeW};
+use crate::interpreter::structs::RuntimeValue;
+use crate::interpreter::Interpreter;
+use crate::modules::ModuleStorage;
+use crate::parser::structs::ASTNode;
+use std::fmt::{Debug, Formatter};
+use std::process::exit;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, TryLockError};
+
+/// Where a generator stopped, the innermost frame is the last one.
+enum Frame {
+    /// the statements of a code block, `position` is the next one to run
+    Block {
+        code: Vec<ASTNode>,
+        position: usize,
+        scope: RuntimeScopeW,
+    },
+    /// `for` and `repeat`, `body` runs once for every value with `^index` bound, and `^value` too when `bind_value` is set
+    Loop {
+        values: Box<dyn Iterator<Item = RuntimeValue>>,
+        index: usize,
+        body: ASTNode,
+        scope: RuntimeScopeW,
+        bind_value: bool,
+    },
+}
+
+struct GeneratorState {
+    frames: Vec<Frame>,
+    module_storage: Arc<ModuleStorage>,
+}
+
+/// A call of a `gen fn`. Its body runs on frames instead of the Rust stack of `Interpreter::eval`,
+/// so it can stop at a `yield` and continue from there the next time a value is pulled.
+pub struct Generator {
+    pub yield_type: DataType,
+    state: Mutex<GeneratorState>,
+}
+
+impl Debug for Generator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Generator::*")
+    }
+}
+
+impl Generator {
+    /// Runs until the next `yield`, `None` once the body has finished.
+    pub fn resume(&self) -> Option<RuntimeValue> {
+        let mut state = match self.state.try_lock() {
+            Ok(state) => state,
+            // an error was thrown out of the body, the generator has ended
+            Err(TryLockError::Poisoned(_)) => return None,
+            Err(TryLockError::WouldBlock) => {
+                err!(intrp "A generator cannot pull values from itself.");
+                exit(100)
+            }
+        };
+
+        let state = &mut *state;
+        let interpreter = Interpreter::new(ASTNode::Program(vec![]), state.module_storage.clone());
+
+        loop {
+            let (node, scope) = match state.frames.last_mut()? {
+                Frame::Block {
+                    code,
+                    position,
+                    scope,
+                } => {
+                    let Some(node) = code.get(*position).cloned() else {
+                        state.frames.pop();
+                        continue;
+                    };
+
+                    *position += 1;
+
+                    (node, scope.clone())
+                }
+                Frame::Loop {
+                    values,
+                    index,
+                    body,
+                    scope,
+                    bind_value,
+                } => {
+                    let Some(value) = values.next() else {
+                        state.frames.pop();
+                        continue;
+                    };
+
+                    let mut bindings = scope.write().unwrap();
+
+                    bindings.assign_binding(String::from("index"), RuntimeValue::Number(*index as f64));
+                    if *bind_value {
+                        bindings.assign_binding(String::from("value"), value);
+                    }
+
+                    *index += 1;
+
+                    (body.clone(), scope.clone())
+                }
+            };
+
+            if let Some(value) = self.enter(state, &interpreter, &node, scope) {
+                return Some(value);
+            }
+        }
+    }
+
+    /// Runs `node`, statements with a `yield` inside push a frame instead of being evaluated right away.
+    fn enter(
+        &self,
+        state: &mut GeneratorState,
+        interpreter: &Interpreter,
+        node: &ASTNode,
+        scope: RuntimeScopeW,
+    ) -> Option<RuntimeValue> {
+        if !contains_yield(node) {
+            interpreter.eval(node, scope.clone());
+            interpreter.run_pending_destructors(scope);
+
+            return None;
+        }
+
+        match node {
+            ASTNode::Yield(value) => {
+                let value = interpreter.eval(value, scope.clone());
+                let scope = scope.read().unwrap();
+                let value_type = scope.get_value_type(&value);
+
+                if !scope.type_matches(&self.yield_type, &value_type) {
+                    err!(intrp "Expected to yield a value of type `{}`, got `{}`.", self.yield_type, value_type);
+                    exit(100)
+                }
+
+                Some(value)
+            }
+            ASTNode::CodeBlock(code) => {
+                state.frames.push(Frame::Block {
+                    code: code.clone(),
+                    position: 0,
+                    scope: RuntimeScope::arc_rwlock_new(Some(scope)),
+                });
+
+                None
+            }
+            ASTNode::IfStatement(stmt) => match interpreter.eval(&stmt.condition, scope.clone()) {
+                RuntimeValue::Bool(true) => self.enter(state, interpreter, &stmt.if_block, scope),
+                RuntimeValue::Bool(false) => match &stmt.else_block {
+                    Some(else_block) => self.enter(state, interpreter, else_block, scope),
+                    None => None,
+                },
+                _ => {
+                    err!(intrp "Expected a Boolean value as a result");
+                    exit(100)
+                }
+            },
+            ASTNode::OnceStatement(stmt) => {
+                for if_st in &stmt.if_statements {
+                    if let RuntimeValue::Bool(true) = interpreter.eval(&if_st.condition, scope.clone()) {
+                        return self.enter(state, interpreter, &if_st.if_block, scope);
+                    }
+                }
+
+                match &stmt.else_block {
+                    Some(else_block) => self.enter(state, interpreter, else_block, scope),
+                    None => None,
+                }
+            }
+            ASTNode::ForStatement(stmt) => {
+                let iterable = interpreter.eval(&stmt.iterable, scope.clone());
+
+                state.frames.push(Frame::Loop {
+                    values: interpreter.values_of(&iterable, &scope),
+                    index: 0,
+                    body: *stmt.block.clone(),
+                    scope: RuntimeScope::arc_rwlock_new(Some(scope)),
+                    bind_value: true,
+                });
+
+                None
+            }
+            ASTNode::RepeatOperation(count, operation) => {
+                let (values, bind_value) = interpreter.repeat_values(count, scope.clone());
+
+                state.frames.push(Frame::Loop {
+                    values,
+                    index: 0,
+                    body: *operation.clone(),
+                    scope: RuntimeScope::arc_rwlock_new(Some(scope)),
+                    bind_value,
+                });
+
+                None
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Only statements can yield, so only the blocks of statements are searched.
+fn contains_yield(node: &ASTNode) -> bool {
+    match node {
+        ASTNode::Yield(_) => true,
+        ASTNode::CodeBlock(code) => code.iter().any(contains_yield),
+        ASTNode::IfStatement(stmt) => {
+            contains_yield(&stmt.if_block) || stmt.else_block.as_deref().is_some_and(contains_yield)
+        }
+        ASTNode::OnceStatement(stmt) => {
+            stmt.if_statements.iter().any(|if_st| contains_yield(&if_st.if_block))
+                || stmt.else_block.as_deref().is_some_and(contains_yield)
+        }
+        ASTNode::ForStatement(stmt) => contains_yield(&stmt.block),
+        ASTNode::RepeatOperation(_, operation) => contains_yield(operation),
+        _ => false,
+    }
+}
+
+impl Interpreter {
+    /// The body of a `gen fn` doesn't run when it's called, it runs in the scope of the call as values are pulled.
+    pub(super) fn eval_generator(
+        &self,
+        code: &[ASTNode],
+        yield_type: &DataType,
+        scope: RuntimeScopeW,
+    ) -> RuntimeValue {
+        RuntimeValue::Generator(Rc::new(Generator {
+            yield_type: yield_type.clone(),
+            state: Mutex::new(GeneratorState {
+                frames: vec![Frame::Block {
+                    code: code.to_vec(),
+                    position: 0,
+                    scope,
+                }],
+                module_storage: self.module_storage.clone(),
+            }),
+        }))
+    }
+}
@@ -1,3 +1,6 @@
+pub mod derive;
+pub mod exceptions;
+pub mod generator;
 pub mod scope;
 pub mod structs;
 
@@ -6,14 +9,20 @@ use crate::global::DataType;
 use crate::interpreter::scope::{FnArgs, RuntimeScope};
 use crate::interpreter::scope::{FunctionData, RuntimeScopeW};
 use crate::interpreter::structs::ComplexRuntimeValue;
-use crate::interpreter::structs::{EnumData, IterablePair, LayoutData, Reference, RuntimeValue};
+use crate::interpreter::structs::{
+    set_function_evaluator, take_pending_destructors, EnumData, LayoutData, RangeData,
+    Reference, RuntimeValue,
+};
 use crate::modules::{ModuleExport, ModuleStorage};
 use crate::parser::structs::{
-    ASTNode, AssignmentProperty, BinaryExpression, ExpressionType, ForStatement, IfStatement,
-    LayoutCreation, LayoutDeclaration, OnceStatement, Operand, ParserFunctionData, UseNative,
+    ASTNode, AssignmentProperty, BinaryExpression, ClassDeclaration, ExpressionType, ForStatement,
+    IfStatement, LayoutCreation, LayoutDeclaration, ModuleImport, OnceStatement, Operand,
+    ParserFunctionData, RangeExpression, TryStatement, UseNative,
 };
-use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use std::collections::HashSet;
 use std::process::exit;
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 
 pub struct Interpreter {
@@ -39,12 +48,23 @@ impl Interpreter {
 
         let scope = Arc::new(RwLock::new(scope));
 
+        let module_storage = self.module_storage.clone();
+        set_function_evaluator(Rc::new(move |fn_data, args| {
+            let interpreter = Interpreter::new(ASTNode::Program(vec![]), module_storage.clone());
+            let fn_scope = fn_data.scope.clone();
+            interpreter.eval_fn_call_values(fn_data, args, fn_scope)
+        }));
+
         // dbg!(&self.program);
 
         for node in &self.program {
             last_evaluated = self.eval(node, scope.clone());
+            self.run_pending_destructors(scope.clone());
         }
 
+        scope.write().unwrap().drop_variables();
+        self.run_pending_destructors(scope.clone());
+
         last_evaluated
     }
 
@@ -65,6 +85,7 @@ impl Interpreter {
 
                 for node in body {
                     last_evaluated = self.eval(node, scope.clone());
+                    self.run_pending_destructors(scope.clone());
                 }
 
                 last_evaluated
@@ -144,9 +165,14 @@ impl Interpreter {
 
                 RuntimeValue::Null
             }
+            ASTNode::ExpandStatement(layout, via) => {
+                scope.read().unwrap().expand_layout(layout.clone(), via.clone());
+
+                RuntimeValue::Null
+            }
             ASTNode::InternalMulti(_) => unreachable!(),
-            ASTNode::UseModule(path, symbol) => {
-                self.eval_module(path.clone(), symbol.clone(), scope.clone())
+            ASTNode::UseModule(path, import) => {
+                self.eval_module(path.clone(), import.clone(), scope.clone())
             }
             ASTNode::Lambda(args, body, return_type) => self.create_lambda(
                 args.clone(),
@@ -158,7 +184,48 @@ impl Interpreter {
                 Self::error(line, file_name);
                 exit(100);
             },
-            &ASTNode::Indexing(_, _) => unreachable!()
+            &ASTNode::Indexing(_, _) => unreachable!(),
+            ASTNode::Range(v) => self.eval_range(v, scope.clone()),
+            ASTNode::ClassDeclaration(v) => {
+                self.eval_class_declaration(*v.clone(), scope);
+                RuntimeValue::Null
+            }
+            ASTNode::ClassConstruction(name) => self.eval_class_construction(name, scope),
+            ASTNode::DerivedFunction(layout, function) => {
+                self.eval_derived_function(layout, function, scope)
+            }
+            ASTNode::Generator(code, yield_type) => self.eval_generator(code, yield_type, scope),
+            ASTNode::Throw(value) => {
+                // a thrown string becomes an `Error`, so it gets a stack
+                match self.eval(value, scope) {
+                    RuntimeValue::String(message) => exceptions::throw(exceptions::error_value(message)),
+                    v => exceptions::throw(v),
+                }
+            }
+            ASTNode::TryStatement(stmt) => self.eval_try_statement(stmt, scope),
+            ASTNode::ResultCreation(ok, value) => {
+                let value = Box::new(self.eval(value, scope));
+
+                RuntimeValue::Result(if *ok { Ok(value) } else { Err(value) })
+            }
+            ASTNode::Yield(_) => {
+                err!(intrp "`yield` can only be used as a statement of a `gen fn`, not inside of an expression.");
+                exit(100)
+            }
+        }
+    }
+
+    fn run_pending_destructors(&self, scope: RuntimeScopeW) {
+        loop {
+            let pending = take_pending_destructors();
+
+            if pending.is_empty() {
+                break;
+            }
+
+            for (destructor, instance) in pending {
+                self.eval_fn_call_values(destructor, vec![instance], scope.clone());
+            }
         }
     }
 
@@ -194,7 +261,7 @@ impl Interpreter {
             Operand::Arrow => RuntimeValue::Null,
             Operand::BackwardArrow => RuntimeValue::Null,
             Operand::ExclamationMk => RuntimeValue::Null,
-            Operand::QuestionMk => RuntimeValue::Null,
+            Operand::QuestionMk => self.eval_question_mark(binary_expression, scope),
             Operand::Plus => self.eval_add_expression(binary_expression, false, scope),
             Operand::Minus => self.eval_add_expression(binary_expression, true, scope),
             Operand::Multiply => self.eval_multiply_expression(binary_expression, false, scope),
@@ -212,10 +279,9 @@ impl Interpreter {
                 self.eval_comparison_expression(binary_expression, true, true, scope)
             }
             Operand::SmallerEqual => {
-                self.eval_comparison_expression(binary_expression, false, false, scope)
+                self.eval_comparison_expression(binary_expression, false, true, scope)
             }
             Operand::Equal => RuntimeValue::Null,
-            Operand::DoubleDot => self.eval_double_dot_expressions(binary_expression, scope),
         }
     }
 
@@ -325,6 +391,7 @@ impl Interpreter {
         scope: RuntimeScopeW,
     ) {
         let eval = self.eval(&value, scope.clone());
+        scope.read().unwrap().check_declaration(&identifier, &type_id, &eval);
         scope
             .write()
             .unwrap()
@@ -339,20 +406,22 @@ impl Interpreter {
     ) {
         let v = self.eval(&value, scope.clone());
         if let AssignmentProperty::Variable(id) = identifier {
-            scope.write().unwrap().assign_variable(id, v);
+            RuntimeScope::assign_variable(&scope, id, v);
         } else if let AssignmentProperty::LayoutField(name, field) = identifier {
             let variable = self.eval(&*name, scope.clone());
 
             let data = self.cast_to_layout_data(variable);
 
-            if !data.entries.read().unwrap().contains_key(&field) {
+            let Some(data) = self.find_field_owner(data.clone(), &field, scope.clone()) else {
                 err!(
                     intrp
                     "Field `{}` does not exist on type `{}`.",
                     &field, &data.layout_id
                 );
                 exit(100)
-            }
+            };
+
+            self.check_private_access(&data, &field, scope.clone());
 
             data
                 .entries
@@ -363,18 +432,40 @@ impl Interpreter {
     }
     //
     fn eval_repeat_operation(&self, count: ASTNode, operation: ASTNode, scope: RuntimeScopeW) {
-        let count_rv = self.eval(&count, scope.clone());
+        let (values, bind_value) = self.repeat_values(&count, scope.clone());
         let scope_bound = Arc::new(RwLock::new(RuntimeScope::new(Some(scope))));
-        if let RuntimeValue::Number(count) = count_rv {
-            for idx in 0..count.floor().abs() as u32 {
+
+        for (idx, value) in values.enumerate() {
+            scope_bound
+                .write()
+                .unwrap()
+                .assign_binding(String::from("index"), RuntimeValue::Number(idx as f64));
+            if bind_value {
                 scope_bound
                     .write()
                     .unwrap()
-                    .assign_binding(String::from("index"), RuntimeValue::Number(idx as f64));
-                self.eval(&operation, scope_bound.clone());
+                    .assign_binding(String::from("value"), value);
             }
+            self.eval(&operation, scope_bound.clone());
+        }
+    }
+
+    /// What the repeat operator (?:) goes through, a number of times, or the values of an iterable with `^value` bound.
+    fn repeat_values(
+        &self,
+        count: &ASTNode,
+        scope: RuntimeScopeW,
+    ) -> (Box<dyn Iterator<Item = RuntimeValue>>, bool) {
+        let count_rv = self.eval(count, scope);
+
+        if let RuntimeValue::Number(count) = count_rv {
+            let times = count.floor().abs() as u32;
+
+            (Box::new((0..times).map(|idx| RuntimeValue::Number(idx as f64))), false)
+        } else if let Some(values) = count_rv.iter_values() {
+            (values, true)
         } else {
-            err!(intrp "The value on the right of the repeat operator (?:) cannot be evaluated into a number.");
+            err!(intrp "The value on the right of the repeat operator (?:) cannot be evaluated into a number or iterated over.");
             exit(100)
         }
     }
@@ -404,6 +495,11 @@ impl Interpreter {
             is_ident = true;
             extracted_name = t;
         }
+
+        if is_ident && extracted_name == "super" {
+            return self.eval_super_constructor_call(args, scope);
+        }
+
         let native = scope
             .read()
             .unwrap()
@@ -449,14 +545,36 @@ impl Interpreter {
         args: Vec<ASTNode>,
         scope: RuntimeScopeW,
     ) -> RuntimeValue {
+        let values = args
+            .iter()
+            .take(fn_data.args.len())
+            .map(|x| self.eval(x, scope.clone()))
+            .collect();
+
+        self.eval_fn_call_values(fn_data, values, scope)
+    }
+
+    fn eval_fn_call_values(
+        &self,
+        fn_data: FunctionData,
+        values: Vec<RuntimeValue>,
+        scope: RuntimeScopeW,
+    ) -> RuntimeValue {
+        if values.len() < fn_data.args.len() {
+            err!(intrp
+                "Function `{}` expects {} arguments, got {}",
+                &fn_data.name,
+                fn_data.args.len(),
+                values.len()
+            );
+            exit(100)
+        }
+
         let new_scope = RuntimeScope::arc_rwlock_new(Some(fn_data.scope));
 
-        for (i, (arg, data_type)) in fn_data.args.iter().enumerate() {
-            // dbg!(arg, &args[i]);
-            let ev = self.eval(&args[i], scope.clone());
-            // dbg!(&ev);
+        for ((arg, data_type), ev) in fn_data.args.iter().zip(values) {
             let r#type = scope.read().unwrap().get_value_type(&ev);
-            if !r#type.matches(&data_type) {
+            if !scope.read().unwrap().type_matches(data_type, &r#type) {
                 err!(intrp
                     "Cannot pass value of type `{}` to function argument `{}` of type `{}`",
                     r#type, arg, data_type
@@ -469,15 +587,18 @@ impl Interpreter {
                 .declare_variable(arg.clone(), data_type.clone(), ev, true);
         }
 
-        let r = self.eval(&ASTNode::CodeBlock(fn_data.body), new_scope);
+        exceptions::push_call(&fn_data.name, &fn_data.return_type);
+
+        let r = exceptions::returning(|| self.eval(&ASTNode::CodeBlock(fn_data.body), new_scope));
 
         // dbg!(&r, &fn_data.name);
 
+        let return_type = scope.read().unwrap().get_value_type(&r);
+
         if !scope
             .read()
             .unwrap()
-            .get_value_type(&r)
-            .matches(&fn_data.return_type)
+            .type_matches(&fn_data.return_type, &return_type)
         {
             err!(intrp
                 "Expected type {}, got {}",
@@ -487,9 +608,84 @@ impl Interpreter {
             exit(100)
         }
 
+        exceptions::pop_call();
+
         r
     }
 
+    fn eval_question_mark(&self, expr: BinaryExpression, scope: RuntimeScopeW) -> RuntimeValue {
+        match self.eval(&expr.left, scope.clone()) {
+            RuntimeValue::Result(Ok(v)) => *v,
+            RuntimeValue::Result(Err(e)) => {
+                let returned = RuntimeValue::Result(Err(e));
+
+                let Some((name, return_type)) = exceptions::current_call() else {
+                    err!(intrp "`?` cannot return `{}` outside of a function.", returned);
+                    exit(100)
+                };
+
+                let returned_type = scope.read().unwrap().get_value_type(&returned);
+
+                if !scope.read().unwrap().type_matches(&return_type, &returned_type) {
+                    err!(intrp
+                        "`?` cannot return `{}` from `{}`, which returns `{}`.",
+                        returned, name, return_type
+                    );
+                    exit(100)
+                }
+
+                exceptions::return_early(returned)
+            }
+            v => {
+                err!(intrp "`?` expects a result, got `{}`.", v);
+                exit(100)
+            }
+        }
+    }
+
+    fn eval_try_statement(&self, stmt: &TryStatement, scope: RuntimeScopeW) -> RuntimeValue {
+        match &stmt.finally {
+            // `finally` runs when `?` returns from inside of `try` or `catch` too
+            Some(finally) => exceptions::on_return(
+                || self.eval_try_catch(stmt, scope.clone()),
+                || {
+                    self.eval(finally, scope.clone());
+                },
+            ),
+            None => self.eval_try_catch(stmt, scope),
+        }
+    }
+
+    fn eval_try_catch(&self, stmt: &TryStatement, scope: RuntimeScopeW) -> RuntimeValue {
+        let mut res = exceptions::catch(|| self.eval(&stmt.block, scope.clone()));
+
+        if let (Err(thrown), Some(catch)) = (&res, &stmt.catch) {
+            let catch_scope = RuntimeScope::arc_rwlock_new(Some(scope.clone()));
+
+            if let Some(name) = &catch.name {
+                catch_scope.write().unwrap().declare_variable(
+                    name.clone(),
+                    DataType::InternalInfer,
+                    *thrown.clone(),
+                    false,
+                );
+            }
+
+            // without `finally` there is nothing to run after an error in `catch`
+            res = if stmt.finally.is_some() {
+                exceptions::catch(|| self.eval(&catch.block, catch_scope))
+            } else {
+                Ok(self.eval(&catch.block, catch_scope))
+            };
+        }
+
+        if let Some(finally) = &stmt.finally {
+            self.eval(finally, scope.clone());
+        }
+
+        res.unwrap_or_else(|thrown| exceptions::throw(*thrown))
+    }
+
     fn eval_comparison_expression(
         &self,
         expr: BinaryExpression,
@@ -573,37 +769,33 @@ impl Interpreter {
         scope.read().unwrap().get_binding(name).unwrap()
     }
 
-    fn eval_double_dot_expressions(
-        &self,
-        expr: BinaryExpression,
-        scope: RuntimeScopeW,
-    ) -> RuntimeValue {
-        let lv = self.eval(&expr.left, scope.clone());
-        let rv = self.eval(&expr.right, scope.clone());
+    fn eval_range(&self, expr: &RangeExpression, scope: RuntimeScopeW) -> RuntimeValue {
+        let number = |node: &ASTNode, part: &str| match self.eval(node, scope.clone()) {
+            RuntimeValue::Number(v) if !v.is_nan() => v,
+            v => {
+                err!(intrp "The {} of a range must be a number, got `{}`.", part, v);
+                exit(100)
+            }
+        };
 
-        let r = rv
-            .cast_number()
-            .expect("Cannot get number from the expression.");
-        let l = lv
-            .cast_number()
-            .expect("Cannot get number from the expression.");
-
-        let mut vec = vec![
-            IterablePair {
-                index: 0,
-                value: RuntimeValue::Null
-            };
-            (r - l).floor() as usize
-        ];
+        let start = number(&expr.start, "start");
+        let end = number(&expr.end, "end");
+        let step = match &expr.step {
+            Some(step) => number(step, "step"),
+            None => 1.0,
+        };
 
-        for (idx, val) in vec.iter_mut().enumerate() {
-            *val = IterablePair {
-                index: idx,
-                value: RuntimeValue::Number(l.floor() + idx as f64),
-            }
+        if step <= 0.0 || !step.is_finite() {
+            err!(intrp "The step of a range must be a positive number, got `{}`. Ranges count down when the end is below the start.", step);
+            exit(100)
         }
 
-        RuntimeValue::Iterable(vec)
+        RuntimeValue::Range(RangeData {
+            start,
+            end,
+            step,
+            inclusive: expr.inclusive,
+        })
     }
 
     fn eval_for_statement(&self, stmt: &ForStatement, scope: RuntimeScopeW) {
@@ -611,24 +803,37 @@ impl Interpreter {
 
         let ev_iterable = self.eval(&stmt.iterable, scope.clone());
 
-        let iterable = ev_iterable
-            .cast_iterable()
-            .expect("Cannot get iterable from the expression!");
-
-        for val in iterable.iter() {
+        // values are pulled one by one, so long ranges are never collected
+        for (index, value) in self.values_of(&ev_iterable, &scope).enumerate() {
             scope_bound.write().unwrap().assign_binding(
                 String::from("index"),
-                RuntimeValue::Number(val.index as f64),
+                RuntimeValue::Number(index as f64),
             );
             scope_bound
                 .write()
                 .unwrap()
-                .assign_binding(String::from("value"), val.value.clone());
+                .assign_binding(String::from("value"), value);
 
             self.eval(&stmt.block, scope_bound.clone());
         }
     }
 
+    fn values_of(
+        &self,
+        iterable: &RuntimeValue,
+        scope: &RuntimeScopeW,
+    ) -> Box<dyn Iterator<Item = RuntimeValue>> {
+        let Some(values) = iterable.iter_values() else {
+            err!(intrp
+                "Cannot iterate over a value of type `{}`, layouts need a tied `iter` or `next` function.",
+                scope.read().unwrap().get_value_type(iterable)
+            );
+            exit(100)
+        };
+
+        values
+    }
+
     fn eval_complex_type_access(
         &self,
         complex_id: &String,
@@ -638,7 +843,7 @@ impl Interpreter {
         if let Some(val) = scope.read().unwrap().get_enum_data(complex_id) {
             if val.entries.contains(entry) {
                 RuntimeValue::Complex(ComplexRuntimeValue::Enum(EnumData {
-                    enum_id: complex_id.clone(),
+                    enum_id: val.name.clone(),
                     entry: entry.clone(),
                 }))
             } else {
@@ -689,7 +894,14 @@ impl Interpreter {
     }
 
     fn eval_layout_declaration(&self, layout_declaration: LayoutDeclaration, scope: RuntimeScopeW) {
-        scope.write().unwrap().declare_layout(layout_declaration)
+        let name = layout_declaration.name.clone();
+        let derives = layout_declaration.derives.clone();
+
+        RuntimeScope::declare_layout(scope.clone(), layout_declaration);
+
+        if !derives.is_empty() {
+            RuntimeScope::derive_for_layout(scope, name, derives);
+        }
     }
 
     fn eval_layout_creation(
@@ -716,40 +928,40 @@ impl Interpreter {
             .get_layout_declaration(&layout_creation.name)
             .unwrap();
 
-        let mut fields: HashMap<String, RuntimeValue> = HashMap::new();
-
-        for (name, data) in decl.fields.clone() {
-            if data.default_value.is_some() {
-                let ev = self.eval(&data.default_value.unwrap(), scope.clone());
-                fields.insert(name, ev);
-            }
+        if decl.class.is_some() {
+            err!(intrp
+                "`{}` is a class, create it with `{}->new(...)`.",
+                &layout_creation.name, &layout_creation.name
+            );
+            exit(100)
         }
 
-        for (name, data) in layout_creation.specified_fields {
-            let ev = self.eval(&data, scope.clone());
-
-            if !&decl.fields.contains_key(&name) {
+        for name in layout_creation.specified_fields.keys() {
+            if !&decl.fields.contains_key(name) {
                 err!(intrp
                     "Field `{}` in layout `{}` does not exist.",
                     name, &layout_creation.name
                 );
                 exit(100)
             }
-
-            fields.insert(name, ev);
         }
 
-        for (name, _) in decl.fields.clone() {
-            if !fields.contains_key(&name) {
+        let mut fields: IndexMap<String, RuntimeValue> = IndexMap::new();
+
+        for (name, data) in decl.fields.clone() {
+            let ev = if let Some(specified) = layout_creation.specified_fields.get(&name) {
+                self.eval(specified, scope.clone())
+            } else if let Some(default_value) = data.default_value {
+                self.eval(&default_value, scope.clone())
+            } else {
                 err!(intrp "Field `{}` in layout `{}` is not defined when creating and does not have a default value.", name, &layout_creation.name);
                 exit(100)
-            }
+            };
+
+            fields.insert(name, ev);
         }
 
-        RuntimeValue::Complex(ComplexRuntimeValue::Layout(Arc::new(LayoutData {
-            layout_id: layout_creation.name,
-            entries: Arc::new(RwLock::new(fields)),
-        })))
+        LayoutData::new(decl.name.clone(), fields, decl.mixed.clone()).into_value()
     }
 
     fn eval_layout_field_access(
@@ -758,47 +970,107 @@ impl Interpreter {
         field: String,
         scope: RuntimeScopeW,
     ) -> RuntimeValue {
-        let variable = self.eval(&*name, scope.clone());
+        if *name == ASTNode::Identifier("super".to_string()) {
+            return self.eval_super_access(field, scope);
+        }
 
-        let ty = scope.read().unwrap().get_value_type(&variable).to_string();
+        let variable = self.eval(&*name, scope.clone());
 
         let data = self.cast_to_layout_data(variable);
 
-        if let Some(decl) = scope.clone().read().unwrap().get_layout_declaration(&ty) {
-            if let Some(fun) = decl.mixed.read().unwrap().get(&field) {
-                if fun.tied {
-                    RuntimeValue::Reference(Reference::MethodLikeFunction(fun.clone(), name, scope))
-                } else {
-                    err!(intrp
-                        "Function `{}` is not a tied function on type `{}`.",
-                        &field, &data.layout_id
-                    );
-                    exit(100);
-                }
+        match self.resolve_layout_member(&data, name, &field, scope) {
+            Some(v) => v,
+            None => {
+                err!(intrp
+                    "Field or function `{}` does not exist on type `{}`.",
+                    &field, &data.layout_id
+                );
+                exit(100)
+            }
+        }
+    }
+
+    /// Looks the member up on the layout itself, then through its `expand`-ed fields.
+    fn resolve_layout_member(
+        &self,
+        data: &Arc<LayoutData>,
+        access: Box<ASTNode>,
+        field: &String,
+        scope: RuntimeScopeW,
+    ) -> Option<RuntimeValue> {
+        self.check_private_access(data, field, scope.clone());
+
+        let decl = scope.read().unwrap().get_layout_declaration(&data.layout_id);
+
+        // the instance keeps its functions, so they are found even if the layout is imported under another name
+        let method = data.methods.read().unwrap().get(field).cloned();
+
+        if let Some(fun) = method {
+            if fun.tied {
+                return Some(RuntimeValue::Reference(Reference::MethodLikeFunction(
+                    fun, access, scope,
+                )));
             } else {
-                data.entries
-                    .clone()
-                    .read()
-                    .unwrap()
-                    .get(&field)
-                    .expect(&format!(
-                        "Field or function `{}` does not exist on type `{}`.",
-                        &field, &data.layout_id
-                    ))
-                    .clone()
+                err!(intrp
+                    "Function `{}` is not a tied function on type `{}`.",
+                    field, &data.layout_id
+                );
+                exit(100);
+            }
+        }
+
+        if let Some(v) = data.entries.read().unwrap().get(field) {
+            return Some(v.clone());
+        }
+
+        let via = decl
+            .map(|d| d.via.read().unwrap().clone())
+            .unwrap_or_default();
+
+        for via_field in via {
+            let value = data.entries.read().unwrap().get(&via_field).cloned();
+
+            if let Some(RuntimeValue::Complex(ComplexRuntimeValue::Layout(inner))) = value {
+                let inner_access = Box::new(ASTNode::LayoutFieldAccess(access.clone(), via_field));
+
+                if let Some(v) = self.resolve_layout_member(&inner, inner_access, field, scope.clone()) {
+                    return Some(v);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds the layout instance that actually holds the field, following `expand`-ed fields.
+    fn find_field_owner(
+        &self,
+        data: Arc<LayoutData>,
+        field: &String,
+        scope: RuntimeScopeW,
+    ) -> Option<Arc<LayoutData>> {
+        if data.entries.read().unwrap().contains_key(field) {
+            return Some(data);
+        }
+
+        let via = scope
+            .read()
+            .unwrap()
+            .get_layout_declaration(&data.layout_id)
+            .map(|d| d.via.read().unwrap().clone())
+            .unwrap_or_default();
+
+        for via_field in via {
+            let value = data.entries.read().unwrap().get(&via_field).cloned();
+
+            if let Some(RuntimeValue::Complex(ComplexRuntimeValue::Layout(inner))) = value {
+                if let Some(owner) = self.find_field_owner(inner, field, scope.clone()) {
+                    return Some(owner);
+                }
             }
-        } else {
-            data.entries
-                .clone()
-                .read()
-                .unwrap()
-                .get(&field)
-                .expect(&format!(
-                    "Field `{}` does not exist on type `{}`.",
-                    &field, &data.layout_id
-                ))
-                .clone()
         }
+
+        None
     }
 
     fn cast_to_layout_data(&self, variable: RuntimeValue) -> Arc<LayoutData> {
@@ -816,11 +1088,202 @@ impl Interpreter {
         }
     }
 
+    fn check_private_access(&self, data: &LayoutData, field: &String, scope: RuntimeScopeW) {
+        let scope = scope.read().unwrap();
+
+        let is_private = scope
+            .get_layout_declaration(&data.layout_id)
+            .and_then(|d| d.class.as_ref().map(|c| c.private.contains(field)))
+            .unwrap_or(false);
+
+        if !is_private {
+            return;
+        }
+
+        let allowed = scope.get_enclosing_class().is_some_and(|class| {
+            scope.is_subclass_of(&data.layout_id, &class)
+                || scope.is_subclass_of(&class, &data.layout_id)
+        });
+
+        if !allowed {
+            err!(intrp
+                "`{}` is private in class `{}`, and can only be accessed from its members.",
+                field, &data.layout_id
+            );
+            exit(100)
+        }
+    }
+
+    fn get_super_class(&self, scope: RuntimeScopeW) -> String {
+        let class = match scope.read().unwrap().get_enclosing_class() {
+            Some(v) => v,
+            None => {
+                err!(intrp "`super` can only be used inside class members.");
+                exit(100)
+            }
+        };
+
+        let parent = scope
+            .read()
+            .unwrap()
+            .get_layout_declaration(&class)
+            .and_then(|d| d.class.as_ref().and_then(|c| c.parent.clone()));
+
+        match parent {
+            Some(v) => v,
+            None => {
+                err!(intrp "Class `{}` does not extend any class, so it has no `super`.", class);
+                exit(100)
+            }
+        }
+    }
+
+    fn eval_super_access(&self, field: String, scope: RuntimeScopeW) -> RuntimeValue {
+        let parent = self.get_super_class(scope.clone());
+
+        let decl = scope
+            .read()
+            .unwrap()
+            .get_layout_declaration(&parent)
+            .unwrap();
+
+        let fun = decl.mixed.read().unwrap().get(&field).cloned();
+
+        match fun {
+            Some(fun) if fun.tied => RuntimeValue::Reference(Reference::MethodLikeFunction(
+                fun,
+                Box::new(ASTNode::Identifier("self".to_string())),
+                scope,
+            )),
+            Some(fun) => RuntimeValue::Reference(Reference::Function(fun)),
+            None => {
+                err!(intrp "No function `{}` was found in class `{}`", &field, &parent);
+                exit(100)
+            }
+        }
+    }
+
+    fn eval_super_constructor_call(&self, args: Vec<ASTNode>, scope: RuntimeScopeW) -> RuntimeValue {
+        let parent = self.get_super_class(scope.clone());
+
+        let instance = self.get_identifier_value("self".to_string(), scope.clone());
+        let values = args.iter().map(|x| self.eval(x, scope.clone())).collect();
+
+        self.run_constructor(&parent, instance, values, scope);
+
+        RuntimeValue::Null
+    }
+
+    fn eval_class_declaration(&self, declaration: ClassDeclaration, scope: RuntimeScopeW) {
+        RuntimeScope::declare_class(scope, declaration)
+    }
+
+    fn eval_class_construction(&self, name: &String, scope: RuntimeScopeW) -> RuntimeValue {
+        let decl = scope
+            .read()
+            .unwrap()
+            .get_layout_declaration(name)
+            .unwrap();
+
+        let mut fields: IndexMap<String, RuntimeValue> = IndexMap::new();
+
+        for (field, data) in decl.fields.clone() {
+            let value = match data.default_value {
+                Some(v) => self.eval(&v, scope.clone()),
+                None => RuntimeValue::Null,
+            };
+            fields.insert(field, value);
+        }
+
+        let mut destructors = vec![];
+        let mut current = Some(decl.clone());
+
+        while let Some(d) = current {
+            let class = d.class.clone();
+            if let Some(destructor) = class.as_ref().and_then(|c| c.destructor.clone()) {
+                destructors.push(destructor);
+            }
+            current = class
+                .and_then(|c| c.parent)
+                .and_then(|p| scope.read().unwrap().get_layout_declaration(&p));
+        }
+
+        let mut data = LayoutData::new(name.clone(), fields, decl.mixed.clone());
+        data.destructors = destructors;
+        data.private = decl.class.as_ref().map(|class| class.private.clone());
+
+        let instance = data.into_value();
+
+        // the arguments of `new` are declared in the current scope
+        let new_args = decl.mixed.read().unwrap().get("new").unwrap().args.clone();
+        let values = new_args
+            .keys()
+            .map(|arg| self.get_identifier_value(arg.clone(), scope.clone()))
+            .collect();
+
+        self.run_constructor(name, instance.clone(), values, scope);
+
+        instance
+    }
+
+    fn run_constructor(
+        &self,
+        class_name: &String,
+        instance: RuntimeValue,
+        values: Vec<RuntimeValue>,
+        scope: RuntimeScopeW,
+    ) {
+        let class = match scope
+            .read()
+            .unwrap()
+            .get_layout_declaration(class_name)
+            .and_then(|d| d.class.clone())
+        {
+            Some(v) => v,
+            None => return,
+        };
+
+        let Some(constructor) = class.constructor else {
+            if let Some(parent) = &class.parent {
+                self.run_constructor(parent, instance, values, scope);
+            }
+            return;
+        };
+
+        let calls_super = constructor.body.iter().any(|node| {
+            matches!(node, ASTNode::FunctionCall(ident, _) if **ident == ASTNode::Identifier("super".to_string()))
+        });
+
+        if let Some(parent) = class.parent.as_ref().filter(|_| !calls_super) {
+            let parent_args = scope
+                .read()
+                .unwrap()
+                .get_layout_declaration(parent)
+                .and_then(|d| d.mixed.read().unwrap().get("new").map(|n| n.args.len()))
+                .unwrap_or(0);
+
+            if parent_args != 0 {
+                err!(intrp
+                    "The constructor of `{}` must call `super(...)`, as `{}` requires constructor arguments.",
+                    class_name, parent
+                );
+                exit(100)
+            }
+
+            self.run_constructor(parent, instance.clone(), vec![], scope.clone());
+        }
+
+        let mut args = vec![instance];
+        args.extend(values);
+
+        self.eval_fn_call_values(constructor, args, scope);
+    }
+
     fn eval_layout_mix(&self, layout: String, mix: Vec<ParserFunctionData>, scope: RuntimeScopeW) {
         RuntimeScope::mix_into_layout(scope.clone(), layout.clone(), mix);
     }
 
-    fn eval_module(&self, path: String, symbol: String, scope: RuntimeScopeW) -> RuntimeValue {
+    fn eval_module(&self, path: String, import: ModuleImport, scope: RuntimeScopeW) -> RuntimeValue {
         let module = self.module_storage.get(&path).unwrap();
 
         module.scope.write().unwrap().preplace_native_functions(scope.read().unwrap().get_native_functions());
@@ -856,13 +1319,42 @@ impl Interpreter {
                 module.push(k.clone(), ModuleExport::Layout(r.unwrap().clone()))
             }
 
+            for k in module.unmodulated_exported_symbols() {
+                let r = module.scope().read().unwrap().resolve_export(&k);
+
+                module.push(k.clone(), r.unwrap())
+            }
+
             module.cache(v);
         }
 
-        scope.read().unwrap().import(
-            symbol.clone(),
-            module.exports().get(&symbol).unwrap().clone(),
-        );
+        let exports = module.exports();
+
+        match import {
+            ModuleImport::Symbols(symbols) => {
+                for (symbol, local) in symbols {
+                    let Some(export) = exports.get(&symbol) else {
+                        err!(intrp "Module `{}` does not export `{}`.", path, symbol);
+                        exit(100)
+                    };
+
+                    scope.read().unwrap().import(local, export.clone());
+                }
+            }
+            ModuleImport::All => {
+                for (symbol, export) in exports {
+                    scope.read().unwrap().import(symbol, export);
+                }
+            }
+            ModuleImport::Namespace(namespace) => {
+                for (symbol, export) in exports {
+                    scope
+                        .read()
+                        .unwrap()
+                        .import(format!("{}::{}", namespace, symbol), export);
+                }
+            }
+        }
 
         module.cached_result().unwrap()
     }
@@ -3,7 +3,8 @@ use crate::global::{DataType, NumType, PrimitiveDataType};
 use crate::interpreter::structs::{ComplexRuntimeValue, MoSaNativeFunction, Reference, RuntimeValue};
 use crate::modules::ModuleExport;
 use crate::parser::structs::{
-    ASTNode, FieldParserDescription, LayoutDeclaration, ParserFunctionData,
+    ASTNode, ClassDeclaration, Derive, FieldParserDescription, LayoutDeclaration,
+    ParserFunctionData,
 };
 use indexmap::IndexMap;
 use std::collections::{HashMap, HashSet};
@@ -43,8 +44,42 @@ pub struct EnumDefinition {
 #[derive(Clone, Debug)]
 pub struct ScopeLayoutDeclaration {
     pub name: String,
-    pub fields: HashMap<String, FieldParserDescription>,
+    pub fields: IndexMap<String, FieldParserDescription>,
     pub mixed: Arc<RwLock<HashMap<String, FunctionData>>>,
+    /// fields, which members are forwarded with `expand`
+    pub via: Arc<RwLock<Vec<String>>>,
+    /// layouts embedded with `..Name`, including the ones they embed themselves
+    pub embeds: Vec<String>,
+    pub class: Option<ClassData>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClassData {
+    pub parent: Option<String>,
+    /// a tied function, taking `self` and the constructor arguments
+    pub constructor: Option<FunctionData>,
+    pub destructor: Option<FunctionData>,
+    pub private: HashSet<String>,
+}
+
+impl ScopeLayoutDeclaration {
+    pub fn new(
+        name: String,
+        fields: IndexMap<String, FieldParserDescription>,
+        mixed: HashMap<String, FunctionData>,
+        via: Vec<String>,
+        embeds: Vec<String>,
+        class: Option<ClassData>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            fields,
+            mixed: Arc::new(RwLock::new(mixed)),
+            via: Arc::new(RwLock::new(via)),
+            embeds,
+            class,
+        })
+    }
 }
 
 // #[derive(Debug)]
@@ -58,6 +93,7 @@ pub struct RuntimeScope {
     enums: HashMap<String, EnumDefinition>,
     layouts: HashMap<String, Arc<ScopeLayoutDeclaration>>,
     imports: RwLock<HashMap<String, ModuleExport>>,
+    class: Option<String>,
 }
 
 impl Debug for RuntimeScope {
@@ -78,6 +114,7 @@ impl RuntimeScope {
             enums: Default::default(),
             layouts: Default::default(),
             imports: Default::default(),
+            class: None,
         }
     }
 
@@ -85,6 +122,19 @@ impl RuntimeScope {
         Arc::new(RwLock::new(Self::new(parent)))
     }
 
+    /// Checked before `declare_variable`, so the error isn't thrown while the scope is locked for writing,
+    /// which would leave its lock poisoned after `catch`.
+    pub fn check_declaration(&self, name: &str, type_id: &DataType, value: &RuntimeValue) {
+        let value_type = self.get_value_type(value);
+        if *type_id != DataType::InternalInfer && !self.type_matches(type_id, &value_type) {
+            err!(intrp
+                "Cannot declare variable `{}` of type `{}` with value of type `{}`",
+                name, type_id, value_type
+            );
+            exit(100)
+        }
+    }
+
     pub fn declare_variable(
         &mut self,
         name: String,
@@ -93,13 +143,6 @@ impl RuntimeScope {
         is_immut: bool,
     ) {
         let value_type = self.get_value_type(&value);
-        if type_id != DataType::InternalInfer && !type_id.matches(&value_type) {
-            err!(intrp
-                "Cannot declare variable `{}` of type `{}` with value of type `{}`",
-                &name, &type_id, value_type
-            );
-            exit(100)
-        }
         self.variables.insert(
             name,
             VariableData {
@@ -120,14 +163,27 @@ impl RuntimeScope {
         } else {
             if let Some(parent) = &self.parent {
                 parent.read().unwrap().read_variable(name)
+            } else if let Some(ModuleExport::Value(v)) = self.get_import(&name) {
+                Some(v)
             } else {
                 None
             }
         }
     }
 
-    pub fn assign_variable(&mut self, name: String, value: RuntimeValue) {
-        if let Some(variable) = self.variables.get(&name) {
+    /// The scope declaring the variable is only locked for writing once the value is checked,
+    /// an error thrown while the lock is held would leave it poisoned after `catch`.
+    pub fn assign_variable(scope: &RuntimeScopeW, name: String, value: RuntimeValue) {
+        let mut next = Some(scope.clone());
+
+        while let Some(current) = next {
+            let read = current.read().unwrap();
+
+            let Some(variable) = read.variables.get(&name) else {
+                next = read.parent.clone();
+                continue;
+            };
+
             if variable.immut {
                 err!(intrp
                     "Cannot reassign the variable {}, as it's declared as immutable",
@@ -135,30 +191,28 @@ impl RuntimeScope {
                 );
                 exit(100);
             }
-            let value_type = self.get_value_type(&value);
-            if !variable.type_id.matches(&value_type) {
+            let value_type = read.get_value_type(&value);
+            if !read.type_matches(&variable.type_id, &value_type) {
                 err!(intrp
                     "Cannot assign value of type `{}` to variable `{}` of type `{}`.",
                     value_type, &name, &variable.type_id
                 );
                 exit(100)
             }
-            self.variables.insert(
-                name,
-                VariableData {
-                    value: RwLock::new(value.clone()),
-                    type_id: variable.type_id.clone(),
-                    immut: variable.immut,
-                },
-            );
-        } else {
-            if let Some(parent) = &self.parent {
-                parent.write().unwrap().assign_variable(name, value)
-            } else {
-                err!(intrp "Cannot assign the variable {}, as it's not declared", name);
-                exit(100);
-            }
+            let data = VariableData {
+                value: RwLock::new(value),
+                type_id: variable.type_id.clone(),
+                immut: variable.immut,
+            };
+
+            drop(read);
+            current.write().unwrap().variables.insert(name, data);
+
+            return;
         }
+
+        err!(intrp "Cannot assign the variable {}, as it's not declared", name);
+        exit(100);
     }
 
     pub fn assign_binding(&mut self, name: String, value: RuntimeValue) {
@@ -242,6 +296,8 @@ impl RuntimeScope {
         } else {
             if let Some(parent) = &self.parent {
                 parent.read().unwrap().get_defined_name(ident)
+            } else if let Some(ModuleExport::NativeFunction(path)) = self.get_import(&ident) {
+                Some(path)
             } else {
                 None
             }
@@ -286,11 +342,22 @@ impl RuntimeScope {
                     DataType::Complex(ComplexDataType::Indefinite)
                 }
             }
-            RuntimeValue::Iterable(_) => {
+            RuntimeValue::Iterable(_) | RuntimeValue::Range(_) => {
                 DataType::Primitive(PrimitiveDataType::Iterable(Box::new(DataType::Primitive(
                     PrimitiveDataType::Num(NumType::Dynamic),
                 ))))
             }
+            RuntimeValue::Generator(v) => {
+                DataType::Primitive(PrimitiveDataType::Iterable(Box::new(v.yield_type.clone())))
+            }
+            RuntimeValue::Result(v) => {
+                let (ok, err) = match v {
+                    Ok(v) => (self.get_value_type(v), DataType::Complex(ComplexDataType::Indefinite)),
+                    Err(v) => (DataType::Complex(ComplexDataType::Indefinite), self.get_value_type(v)),
+                };
+
+                DataType::Primitive(PrimitiveDataType::Result(Box::new(ok), Box::new(err)))
+            }
             RuntimeValue::Reference(v) => match v {
                 Reference::Function(_) => DataType::Reference(ReferenceType::Function),
                 Reference::MethodLikeFunction(_, _, _) => {
@@ -311,23 +378,215 @@ impl RuntimeScope {
         } else {
             if let Some(parent) = &self.parent {
                 parent.read().unwrap().get_enum_data(name)
+            } else if let Some(ModuleExport::Enum(def)) = self.get_import(name) {
+                Some(def)
             } else {
                 None
             }
         }
     }
 
-    pub fn declare_layout(&mut self, layout_info: LayoutDeclaration) {
-        self.layouts.insert(
+    pub fn declare_layout(scope: RuntimeScopeW, layout_info: LayoutDeclaration) {
+        let mut fields = IndexMap::new();
+        let mut embeds = vec![];
+
+        for spread in &layout_info.spreads {
+            match scope.read().unwrap().get_layout_declaration(spread) {
+                Some(v) => {
+                    fields.extend(v.fields.clone());
+                    embeds.push(spread.clone());
+                    embeds.extend(v.embeds.clone());
+                }
+                None => {
+                    err!(intrp
+                        "Cannot embed non-existent layout `{}` into `{}`",
+                        spread, &layout_info.name
+                    );
+                    exit(100)
+                }
+            }
+        }
+
+        fields.extend(layout_info.fields);
+
+        scope.write().unwrap().layouts.insert(
             layout_info.name.clone(),
-            Arc::new(ScopeLayoutDeclaration {
-                name: layout_info.name,
-                fields: layout_info.fields,
-                mixed: Arc::new(RwLock::new(HashMap::new())),
-            }),
+            ScopeLayoutDeclaration::new(layout_info.name, fields, HashMap::new(), vec![], embeds, None),
         );
     }
 
+    pub fn declare_class(scope: RuntimeScopeW, declaration: ClassDeclaration) {
+        let parent = declaration.parent.as_ref().map(|parent| {
+            match scope.read().unwrap().get_layout_declaration(parent) {
+                Some(v) => v,
+                None => {
+                    err!(intrp
+                        "Class `{}` cannot extend non-existent class or layout `{}`",
+                        &declaration.name, parent
+                    );
+                    exit(100)
+                }
+            }
+        });
+
+        let class_scope = RuntimeScope::arc_rwlock_new(Some(scope.clone()));
+        class_scope.write().unwrap().class = Some(declaration.name.clone());
+
+        let to_function_data = |data: ParserFunctionData| FunctionData {
+            name: data.name,
+            args: data.args,
+            body: data.body,
+            return_type: data.return_type,
+            tied: data.tied,
+            scope: class_scope.clone(),
+            accesses: HashSet::new(),
+        };
+
+        let mut fields = IndexMap::new();
+        let mut mixed = HashMap::new();
+        let mut via = vec![];
+        let mut private = HashSet::new();
+
+        if let Some(parent) = &parent {
+            fields.extend(parent.fields.clone());
+            mixed.extend(parent.mixed.read().unwrap().clone());
+            via.extend(parent.via.read().unwrap().clone());
+            if let Some(class) = &parent.class {
+                private.extend(class.private.clone());
+            }
+        }
+
+        fields.extend(declaration.fields);
+        private.extend(declaration.private);
+
+        for function in declaration.functions {
+            mixed.insert(function.name.clone(), to_function_data(function));
+        }
+
+        let constructor = declaration.constructor.map(to_function_data);
+
+        // `new` takes the arguments of the nearest constructor up the chain
+        let constructor_args = match &constructor {
+            Some(ctor) => ctor.args.iter().skip(1).map(|(k, v)| (k.clone(), v.clone())).collect(),
+            None => parent
+                .as_ref()
+                .and_then(|p| p.mixed.read().unwrap().get("new").map(|n| n.args.clone()))
+                .unwrap_or_default(),
+        };
+
+        mixed.insert(
+            "new".to_string(),
+            FunctionData {
+                name: "new".to_string(),
+                args: constructor_args,
+                body: vec![ASTNode::ClassConstruction(declaration.name.clone())],
+                return_type: DataType::Complex(ComplexDataType::LayoutOrEnum(
+                    declaration.name.clone(),
+                )),
+                scope: class_scope.clone(),
+                accesses: HashSet::new(),
+                tied: false,
+            },
+        );
+
+        let class = ClassData {
+            parent: declaration.parent,
+            constructor,
+            destructor: declaration.destructor.map(to_function_data),
+            private,
+        };
+
+        scope.write().unwrap().layouts.insert(
+            declaration.name.clone(),
+            ScopeLayoutDeclaration::new(declaration.name, fields, mixed, via, vec![], Some(class)),
+        );
+    }
+
+    pub fn get_enclosing_class(&self) -> Option<String> {
+        if let Some(class) = &self.class {
+            Some(class.clone())
+        } else if let Some(parent) = &self.parent {
+            parent.read().unwrap().get_enclosing_class()
+        } else {
+            None
+        }
+    }
+
+    /// Whether `child` is `ancestor` itself or inherits from it.
+    pub fn is_subclass_of(&self, child: &str, ancestor: &str) -> bool {
+        let mut current = Some(child.to_string());
+
+        while let Some(name) = current {
+            if name == ancestor {
+                return true;
+            }
+
+            current = self
+                .get_layout_declaration(&name)
+                .and_then(|d| d.class.as_ref().and_then(|c| c.parent.clone()));
+        }
+
+        false
+    }
+
+    /// Whether `layout` or one of its parent classes embeds `embedded` with `..`.
+    pub fn embeds(&self, layout: &str, embedded: &str) -> bool {
+        let mut current = self.get_layout_declaration(&layout.to_string());
+
+        while let Some(decl) = current {
+            if decl.embeds.iter().any(|v| v == embedded) {
+                return true;
+            }
+
+            current = decl
+                .class
+                .as_ref()
+                .and_then(|c| c.parent.as_ref())
+                .and_then(|p| self.get_layout_declaration(p));
+        }
+
+        false
+    }
+
+    /// Like `DataType::matches`, but also accepts instances of subclasses and layouts embedding the expected one,
+    /// as they have all of its fields.
+    pub fn type_matches(&self, expected: &DataType, actual: &DataType) -> bool {
+        if expected.matches(actual) {
+            return true;
+        }
+
+        let expected = match expected {
+            DataType::Primitive(PrimitiveDataType::Nullable(inner)) => &**inner,
+            v => v,
+        };
+
+        match (expected, actual) {
+            (DataType::Complex(ComplexDataType::Indefinite), _) => true,
+            (
+                DataType::Complex(ComplexDataType::LayoutOrEnum(e)),
+                DataType::Complex(ComplexDataType::LayoutOrEnum(a)),
+            ) => self.is_subclass_of(a, e) || self.embeds(a, e),
+            (
+                DataType::Primitive(PrimitiveDataType::Result(expected_ok, expected_err)),
+                DataType::Primitive(PrimitiveDataType::Result(ok, err)),
+            ) => {
+                // the side a value doesn't have is `indefinite`, it matches anything
+                let side_matches = |expected: &DataType, actual: &DataType| {
+                    let indefinite = DataType::Complex(ComplexDataType::Indefinite);
+
+                    *expected == indefinite || *actual == indefinite || self.type_matches(expected, actual)
+                };
+
+                side_matches(expected_ok, ok) && side_matches(expected_err, err)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn drop_variables(&mut self) {
+        self.variables.clear();
+    }
+
     pub fn get_layout_declaration(&self, name: &String) -> Option<Arc<ScopeLayoutDeclaration>> {
         if let Some(layout) = self.layouts.get(name) {
             Some(layout.clone())
@@ -389,10 +648,126 @@ impl RuntimeScope {
         }
     }
 
+    pub fn derive_for_layout(scope: RuntimeScopeW, layout_id: String, derives: Vec<Derive>) {
+        let Some(decl) = scope.read().unwrap().get_layout_declaration(&layout_id) else {
+            err!(intrp "Cannot derive for non-existent layout `{}`", layout_id);
+            exit(100)
+        };
+
+        let layout_type = DataType::Complex(ComplexDataType::LayoutOrEnum(layout_id.clone()));
+
+        let derived = |name: &str, args: Vec<(&str, DataType)>, return_type: DataType, tied: bool| {
+            let mut fn_args: FnArgs = IndexMap::new();
+            if tied {
+                fn_args.insert("self".to_string(), layout_type.clone());
+            }
+            fn_args.extend(args.into_iter().map(|(k, v)| (k.to_string(), v)));
+
+            (
+                name.to_string(),
+                FunctionData {
+                    name: name.to_string(),
+                    args: fn_args,
+                    body: vec![ASTNode::DerivedFunction(layout_id.clone(), name.to_string())],
+                    return_type,
+                    scope: scope.clone(),
+                    accesses: HashSet::new(),
+                    tied,
+                },
+            )
+        };
+
+        let mut mixed = decl.mixed.write().unwrap();
+
+        for derive in derives {
+            match derive {
+                Derive::Default => {
+                    mixed.extend([derived("default", vec![], layout_type.clone(), false)]);
+                }
+                Derive::Display => {
+                    mixed.extend([derived(
+                        "toStr",
+                        vec![],
+                        DataType::Primitive(PrimitiveDataType::Str),
+                        true,
+                    )]);
+                }
+                Derive::Eq => {
+                    mixed.extend([derived(
+                        "eq",
+                        vec![("other", layout_type.clone())],
+                        DataType::Primitive(PrimitiveDataType::Bool),
+                        true,
+                    )]);
+                }
+                Derive::Clone => {
+                    mixed.extend([derived("clone", vec![], layout_type.clone(), true)]);
+                }
+                Derive::Json => {
+                    mixed.extend([
+                        derived(
+                            "toJson",
+                            vec![],
+                            DataType::Primitive(PrimitiveDataType::Str),
+                            true,
+                        ),
+                        derived(
+                            "fromJson",
+                            vec![("json", DataType::Primitive(PrimitiveDataType::Str))],
+                            layout_type.clone(),
+                            false,
+                        ),
+                    ]);
+                }
+            }
+        }
+    }
+
+    pub fn expand_layout(&self, layout_id: String, via: Vec<String>) {
+        let Some(decl) = self.get_layout_declaration(&layout_id) else {
+            err!(intrp "Cannot expand non-existent layout `{}`", layout_id);
+            exit(100)
+        };
+
+        for field in &via {
+            let Some(description) = decl.fields.get(field) else {
+                err!(intrp "Cannot expand `{}` via `{}`, as it has no such field", layout_id, field);
+                exit(100)
+            };
+
+            if self.get_layout_declaration(&description.type_id).is_none() {
+                err!(intrp
+                    "Cannot expand `{}` via `{}`, as its type `{}` is not a layout",
+                    layout_id, field, &description.type_id
+                );
+                exit(100)
+            }
+        }
+
+        decl.via.write().unwrap().extend(via);
+    }
+
     pub fn get_import(&self, symbol: &String) -> Option<ModuleExport> {
         self.imports.read().unwrap().get(symbol).cloned()
     }
 
+    /// Looks up a symbol declared or imported in this scope, so a module can export it.
+    pub fn resolve_export(&self, symbol: &String) -> Option<ModuleExport> {
+        if let Some(variable) = self.variables.get(symbol) {
+            Some(ModuleExport::Value(variable.value.read().unwrap().clone()))
+        } else if let Some(def) = self.enums.get(symbol) {
+            Some(ModuleExport::Enum(def.clone()))
+        } else if let Some(path) = self.defined_native_functions.get(symbol) {
+            Some(ModuleExport::NativeFunction(path.clone()))
+        } else if let Some(layout) = self.layouts.get(symbol) {
+            Some(ModuleExport::Layout(layout.clone()))
+        } else if let Some(function) = self.functions.get(symbol) {
+            Some(ModuleExport::Function(function.clone()))
+        } else {
+            self.get_import(symbol)
+        }
+    }
+
     pub fn import(&self, symbol: String, export: ModuleExport) {
         self.imports.write().unwrap().insert(symbol, export);
     }
@@ -1,13 +1,38 @@
+use crate::interpreter::generator::Generator;
 use crate::interpreter::scope::FunctionData;
 use crate::interpreter::RuntimeScopeW;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Add, Div, Mul, Sub};
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
 use crate::parser::structs::ASTNode;
 
 pub type MoSaNativeFunction = Arc<dyn Fn(Vec<RuntimeValue>) -> RuntimeValue>;
 
+/// Calls a MoSa function from outside the interpreter, e.g. from `Display` or native functions.
+pub type FunctionEvaluator = Rc<dyn Fn(FunctionData, Vec<RuntimeValue>) -> RuntimeValue>;
+
+thread_local! {
+    static FUNCTION_EVALUATOR: RefCell<Option<FunctionEvaluator>> = const { RefCell::new(None) };
+}
+
+pub fn set_function_evaluator(evaluator: FunctionEvaluator) {
+    FUNCTION_EVALUATOR.with(|e| *e.borrow_mut() = Some(evaluator));
+}
+
+/// Returns `None` if no interpreter is running on this thread.
+pub fn call_function(function: FunctionData, args: Vec<RuntimeValue>) -> Option<RuntimeValue> {
+    let evaluator = FUNCTION_EVALUATOR
+        .try_with(|e| e.borrow().clone())
+        .ok()
+        .flatten()?;
+
+    Some(evaluator(function, args))
+}
+
 #[derive(Debug, Clone)]
 pub enum RuntimeValue {
     Number(f64),
@@ -15,6 +40,10 @@ pub enum RuntimeValue {
     String(String),
     Bool(bool),
     Iterable(Vec<IterablePair>),
+    Range(RangeData),
+    Generator(Rc<Generator>),
+    /// `Ok(value)` and `Err(error)`
+    Result(Result<Box<RuntimeValue>, Box<RuntimeValue>>),
     Complex(ComplexRuntimeValue),
     Reference(Reference),
 }
@@ -42,16 +71,154 @@ pub struct IterablePair {
     pub index: usize,
     pub value: RuntimeValue,
 }
+/// `start..end`, `start..=end` or `start..end step n`, the values are only computed when they're used.
+/// The step is always positive, ranges with `end` below `start` count down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RangeData {
+    pub start: f64,
+    pub end: f64,
+    pub step: f64,
+    pub inclusive: bool,
+}
+
+impl RangeData {
+    pub fn len(&self) -> usize {
+        let span = (self.end - self.start).abs();
+        let steps = (span / self.step).floor();
+
+        if !self.inclusive && steps * self.step == span {
+            steps as usize
+        } else {
+            (steps as usize).saturating_add(1)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn value(&self, index: usize) -> f64 {
+        if self.end < self.start {
+            self.start - index as f64 * self.step
+        } else {
+            self.start + index as f64 * self.step
+        }
+    }
+
+    pub fn values(self) -> impl Iterator<Item = f64> {
+        (0..self.len()).map(move |index| self.value(index))
+    }
+
+    /// The values after the first `count` ones.
+    pub fn skip(&self, count: usize) -> Self {
+        if count >= self.len() {
+            return Self {
+                start: self.end,
+                inclusive: false,
+                ..*self
+            };
+        }
+
+        Self {
+            start: self.value(count),
+            ..*self
+        }
+    }
+
+    /// The same values, last to first.
+    pub fn reversed(&self) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+
+        Self {
+            start: self.value(self.len() - 1),
+            end: self.start,
+            step: self.step,
+            inclusive: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EnumData {
     pub enum_id: String,
     pub entry: String,
 }
 
-#[derive(Debug, Clone)]
+// Not `Clone`, a copy would run the destructors a second time.
+#[derive(Debug)]
 pub struct LayoutData {
     pub layout_id: String,
-    pub entries: Arc<RwLock<HashMap<String, RuntimeValue>>>,
+    /// field values in the declaration order
+    pub entries: Arc<RwLock<IndexMap<String, RuntimeValue>>>,
+    /// the mixed functions of the declaration
+    pub methods: Arc<RwLock<HashMap<String, FunctionData>>>,
+    /// class destructors, the most derived one goes first
+    pub destructors: Vec<FunctionData>,
+    /// the private members of a class instance, `None` for layouts
+    pub private: Option<HashSet<String>>,
+}
+
+pub type PendingDestructor = (FunctionData, RuntimeValue);
+
+thread_local! {
+    static PENDING_DESTRUCTORS: RefCell<Vec<PendingDestructor>> = const { RefCell::new(vec![]) };
+}
+
+impl Drop for LayoutData {
+    // The value may be dropped while a scope is locked, so the destructors
+    // are only queued here, and ran by the interpreter between statements.
+    fn drop(&mut self) {
+        if self.destructors.is_empty() {
+            return;
+        }
+
+        let revived = LayoutData {
+            layout_id: self.layout_id.clone(),
+            entries: self.entries.clone(),
+            methods: self.methods.clone(),
+            destructors: vec![],
+            private: self.private.clone(),
+        }
+        .into_value();
+
+        let destructors = std::mem::take(&mut self.destructors);
+
+        let _ = PENDING_DESTRUCTORS.try_with(|pending| {
+            pending
+                .borrow_mut()
+                .extend(destructors.into_iter().map(|d| (d, revived.clone())))
+        });
+    }
+}
+
+impl LayoutData {
+    /// The data of a layout value, classes set their destructors and private members afterwards.
+    pub fn new(
+        layout_id: String,
+        entries: IndexMap<String, RuntimeValue>,
+        methods: Arc<RwLock<HashMap<String, FunctionData>>>,
+    ) -> Self {
+        Self {
+            layout_id,
+            entries: Arc::new(RwLock::new(entries)),
+            methods,
+            destructors: vec![],
+            private: None,
+        }
+    }
+
+    /// Every layout value is created through here.
+    pub fn into_value(self) -> RuntimeValue {
+        RuntimeValue::Complex(ComplexRuntimeValue::Layout(Arc::new(self)))
+    }
+}
+
+pub fn take_pending_destructors() -> Vec<PendingDestructor> {
+    PENDING_DESTRUCTORS
+        .try_with(|pending| std::mem::take(&mut *pending.borrow_mut()))
+        .unwrap_or_default()
 }
 
 impl Add for RuntimeValue {
@@ -158,6 +325,26 @@ impl PartialEq for RuntimeValue {
             } else {
                 false
             }
+        } else if let RuntimeValue::Range(l) = self {
+            if let RuntimeValue::Range(r) = other {
+                l == r
+            } else {
+                false
+            }
+        } else if let RuntimeValue::Generator(l) = self {
+            if let RuntimeValue::Generator(r) = other {
+                Rc::ptr_eq(l, r)
+            } else {
+                false
+            }
+        } else if let RuntimeValue::Result(l) = self {
+            if let RuntimeValue::Result(r) = other {
+                l == r
+            } else {
+                false
+            }
+        } else if let RuntimeValue::Null = self {
+            matches!(other, RuntimeValue::Null)
         } else {
             false
         }
@@ -190,6 +377,50 @@ impl RuntimeValue {
     }
 }
 
+impl LayoutData {
+    pub fn tied_method(&self, name: &str) -> Option<FunctionData> {
+        self.methods
+            .read()
+            .unwrap()
+            .get(name)
+            .filter(|fun| fun.tied)
+            .cloned()
+    }
+
+    /// Renders as `Name { field = value, ... }`, without calling a mixed `toStr`.
+    /// Private fields are left out of anything derived from the fields, like printing and JSON.
+    pub fn is_private(&self, field: &str) -> bool {
+        self.private.as_ref().is_some_and(|private| private.contains(field))
+    }
+
+    pub fn render_default(&self) -> String {
+        let fields: Vec<String> = self
+            .entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(field, _)| !self.is_private(field))
+            .map(|(field, value)| format!("{} = {}", field, value.render_nested()))
+            .collect();
+
+        if fields.is_empty() {
+            return format!("{} {{ }}", self.layout_id);
+        }
+
+        format!("{} {{ {} }}", self.layout_id, fields.join(", "))
+    }
+}
+
+impl RuntimeValue {
+    /// Strings inside of layouts and iterables are quoted.
+    fn render_nested(&self) -> String {
+        match self {
+            RuntimeValue::String(v) => format!("{:?}", v),
+            v => v.to_string(),
+        }
+    }
+}
+
 impl Display for RuntimeValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let str = match self {
@@ -197,10 +428,37 @@ impl Display for RuntimeValue {
             RuntimeValue::Null => String::from("null"),
             RuntimeValue::String(str) => str.clone(),
             RuntimeValue::Bool(bool) => String::from(if *bool { "true" } else { "false" }),
-            RuntimeValue::Complex(_) => {
-                String::from("Unable to properly convert the value to a string.")
+            RuntimeValue::Complex(ComplexRuntimeValue::Enum(v)) => {
+                format!("{}->{}", v.enum_id, v.entry)
+            }
+            RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) => {
+                match v
+                    .tied_method("toStr")
+                    .and_then(|fun| call_function(fun, vec![self.clone()]))
+                {
+                    Some(RuntimeValue::String(s)) => s,
+                    _ => v.render_default(),
+                }
             }
-            RuntimeValue::Iterable(v) => format!("{:?}", v),
+            RuntimeValue::Iterable(v) => format!(
+                "[{}]",
+                v.iter()
+                    .map(|pair| pair.value.render_nested())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            RuntimeValue::Range(v) => {
+                let dots = if v.inclusive { "..=" } else { ".." };
+
+                if v.step == 1.0 {
+                    format!("{}{}{}", v.start, dots, v.end)
+                } else {
+                    format!("{}{}{} step {}", v.start, dots, v.end, v.step)
+                }
+            }
+            RuntimeValue::Generator(v) => format!("generator[{}]", v.yield_type),
+            RuntimeValue::Result(Ok(v)) => format!("Ok({})", v.render_nested()),
+            RuntimeValue::Result(Err(v)) => format!("Err({})", v.render_nested()),
             RuntimeValue::Reference(v) => match v {
                 Reference::Function(_) => "ref[function]".to_string(),
                 Reference::MethodLikeFunction(..) => "ref[function]".to_string(),
@@ -227,6 +485,54 @@ impl RuntimeValue {
         }
     }
 
+    /// The values of iterables, ranges, generators, the chars of strings, and layouts following the iterator protocol.
+    /// Nothing is collected first, layouts, ranges and generators give their values as they are pulled.
+    pub fn iter_values(&self) -> Option<Box<dyn Iterator<Item = RuntimeValue>>> {
+        match self {
+            RuntimeValue::Iterable(v) => Some(Box::new(v.clone().into_iter().map(|pair| pair.value))),
+            RuntimeValue::Range(v) => Some(Box::new(v.values().map(RuntimeValue::Number))),
+            RuntimeValue::Generator(v) => {
+                let generator = v.clone();
+
+                Some(Box::new(std::iter::from_fn(move || generator.resume())))
+            }
+            RuntimeValue::String(v) => Some(Box::new(
+                v.chars()
+                    .map(|c| RuntimeValue::String(c.to_string()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+            RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) => {
+                // `iter` returns what is iterated, so a collection doesn't have to be its own iterator
+                match v.tied_method("iter") {
+                    Some(iter) => match call_function(iter, vec![self.clone()])? {
+                        v @ RuntimeValue::Complex(ComplexRuntimeValue::Layout(_)) => v.next_values(),
+                        v => v.iter_values(),
+                    },
+                    None => self.next_values(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Calls the tied `next` of a layout until it returns `null`.
+    fn next_values(&self) -> Option<Box<dyn Iterator<Item = RuntimeValue>>> {
+        let RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) = self else {
+            return None;
+        };
+
+        let next = v.tied_method("next")?;
+        let iterator = self.clone();
+
+        Some(Box::new(std::iter::from_fn(move || {
+            match call_function(next.clone(), vec![iterator.clone()]) {
+                None | Some(RuntimeValue::Null) => None,
+                v => v,
+            }
+        })))
+    }
+
     pub fn cast_string(&self) -> Option<&String> {
         if let RuntimeValue::String(l) = self {
             Some(l)
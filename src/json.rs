@@ -0,0 +1,359 @@
+use indexmap::IndexMap;
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(IndexMap<String, JsonValue>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}:{}", self.message, self.line, self.column)
+    }
+}
+
+/// How deep arrays and objects can be nested, so deeply nested input is an error instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+pub fn parse(input: &str) -> Result<JsonValue, JsonError> {
+    let mut parser = JsonParser {
+        chars: input.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+
+    parser.skip_whitespace();
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+
+    if parser.position < parser.chars.len() {
+        return Err(parser.error("Unexpected trailing characters"));
+    }
+
+    Ok(value)
+}
+
+struct JsonParser {
+    chars: Vec<char>,
+    position: usize,
+    /// arrays and objects that are being parsed
+    depth: usize,
+}
+
+impl JsonParser {
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        let consumed = &self.chars[..self.position.min(self.chars.len())];
+        let line = consumed.iter().filter(|c| **c == '\n').count() + 1;
+        let column = consumed.iter().rev().take_while(|c| **c != '\n').count() + 1;
+
+        JsonError {
+            message: message.into(),
+            line,
+            column,
+        }
+    }
+
+    fn curr(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.curr(), Some(' ' | '\n' | '\r' | '\t')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        if self.curr() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected `{}`", expected)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, JsonError> {
+        match self.curr() {
+            Some('{') => self.parse_nested(Self::parse_object),
+            Some('[') => self.parse_nested(Self::parse_array),
+            Some('"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some('t') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some('f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some('n') => self.parse_literal("null", JsonValue::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(self.error(format!("Unexpected character `{}`", c))),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn parse_nested(
+        &mut self,
+        f: fn(&mut Self) -> Result<JsonValue, JsonError>,
+    ) -> Result<JsonValue, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("Arrays and objects cannot be nested more than {} levels deep", MAX_DEPTH)));
+        }
+
+        self.depth += 1;
+        let res = f(self);
+        self.depth -= 1;
+
+        res
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in literal.chars() {
+            if self.curr() != Some(expected) {
+                return Err(self.error(format!("Invalid literal, expected `{}`", literal)));
+            }
+            self.position += 1;
+        }
+
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.position;
+
+        if self.curr() == Some('-') {
+            self.position += 1;
+        }
+
+        while matches!(self.curr(), Some(c) if c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        {
+            self.position += 1;
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+
+        text.parse::<f64>().map(JsonValue::Number).map_err(|_| {
+            self.position = start;
+            self.error(format!("Invalid number `{}`", text))
+        })
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+
+        let mut res = String::new();
+
+        loop {
+            match self.curr() {
+                None => return Err(self.error("Unterminated string")),
+                Some('"') => {
+                    self.position += 1;
+                    return Ok(res);
+                }
+                Some('\\') => {
+                    self.position += 1;
+                    let escaped = match self.curr() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            self.position += 1;
+                            let code = self.parse_unicode_escape()?;
+                            res.push(code);
+                            continue;
+                        }
+                        _ => return Err(self.error("Invalid escape sequence")),
+                    };
+                    res.push(escaped);
+                    self.position += 1;
+                }
+                Some(c) => {
+                    res.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        if self.position + 4 > self.chars.len() {
+            return Err(self.error("Invalid unicode escape"));
+        }
+
+        let hex: String = self.chars[self.position..self.position + 4].iter().collect();
+        let code = u32::from_str_radix(&hex, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.position += 4;
+
+        Ok(code)
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.parse_hex4()?;
+
+        // surrogate pairs are written as two escapes
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.curr() != Some('\\') || self.chars.get(self.position + 1) != Some(&'u') {
+                return Err(self.error("Unpaired surrogate in unicode escape"));
+            }
+            self.position += 2;
+            let low = self.parse_hex4()?;
+            0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape"))
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let mut res = vec![];
+
+        if self.curr() == Some(']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(res));
+        }
+
+        loop {
+            self.skip_whitespace();
+            res.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.curr() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(res));
+                }
+                _ => return Err(self.error("Expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        let mut res = IndexMap::new();
+
+        if self.curr() == Some('}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(res));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+            res.insert(key, self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.curr() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(res));
+                }
+                _ => return Err(self.error("Expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+impl JsonValue {
+    pub fn to_json_string(&self, pretty: bool) -> String {
+        let mut res = String::new();
+        self.write(&mut res, pretty, 0);
+        res
+    }
+
+    fn write(&self, out: &mut String, pretty: bool, depth: usize) {
+        match self {
+            JsonValue::Null => out.push_str("null"),
+            JsonValue::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+            JsonValue::Number(v) => {
+                if v.is_finite() {
+                    out.push_str(&v.to_string())
+                } else {
+                    out.push_str("null")
+                }
+            }
+            JsonValue::String(v) => write_string(out, v),
+            JsonValue::Array(v) => {
+                if v.is_empty() {
+                    out.push_str("[]");
+                    return;
+                }
+                out.push('[');
+                for (i, item) in v.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    newline(out, pretty, depth + 1);
+                    item.write(out, pretty, depth + 1);
+                }
+                newline(out, pretty, depth);
+                out.push(']');
+            }
+            JsonValue::Object(v) => {
+                if v.is_empty() {
+                    out.push_str("{}");
+                    return;
+                }
+                out.push('{');
+                for (i, (key, item)) in v.iter().enumerate() {
+                    if i != 0 {
+                        out.push(',');
+                    }
+                    newline(out, pretty, depth + 1);
+                    write_string(out, key);
+                    out.push(':');
+                    if pretty {
+                        out.push(' ');
+                    }
+                    item.write(out, pretty, depth + 1);
+                }
+                newline(out, pretty, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn newline(out: &mut String, pretty: bool, depth: usize) {
+    if pretty {
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth));
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -45,7 +45,18 @@ pub enum KeywordType {
     Layout,
     Mix,
     Tied,
-    Repeat
+    Repeat,
+    Expand,
+    Derive,
+    As,
+    Gen,
+    Yield,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Ok,
+    Err,
 }
 
 pub fn reserved_keywords<'a>() -> HashMap<&'a str, KeywordType> {
@@ -71,7 +82,18 @@ pub fn reserved_keywords<'a>() -> HashMap<&'a str, KeywordType> {
         ("layout", KeywordType::Layout),
         ("mix", KeywordType::Mix),
         ("tied", KeywordType::Tied),
-        ("repeat", KeywordType::Repeat)
+        ("repeat", KeywordType::Repeat),
+        ("expand", KeywordType::Expand),
+        ("derive", KeywordType::Derive),
+        ("as", KeywordType::As),
+        ("gen", KeywordType::Gen),
+        ("yield", KeywordType::Yield),
+        ("throw", KeywordType::Throw),
+        ("try", KeywordType::Try),
+        ("catch", KeywordType::Catch),
+        ("finally", KeywordType::Finally),
+        ("Ok", KeywordType::Ok),
+        ("Err", KeywordType::Err),
     ])
 }
 
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 use std::string::String;
@@ -11,12 +10,14 @@ use crate::interpreter::Interpreter;
 use crate::interpreter::scope::RuntimeScope;
 use crate::interpreter::structs::RuntimeValue;
 use crate::jni::jni::{assign_global_jvm};
+use crate::modules::resolver::FileSystemResolver;
 use crate::modules::{Module, ModuleStorage};
 use crate::opts::{parseopts, OptValue};
 use crate::parser::Parser;
 
 pub mod global;
 pub mod interpreter;
+pub mod json;
 pub mod lexer;
 pub mod logging;
 pub mod modules;
@@ -24,7 +25,9 @@ pub mod mosa_fs;
 
 pub mod jni;
 pub mod opts;
+pub mod packages;
 pub mod parser;
+pub mod stdlib;
 pub mod wrapper;
 pub mod prelude;
 
@@ -77,9 +80,10 @@ pub extern "system" fn Java_dev_kofeychi_mosajni_MosaJniBinds_eval<'local>(mut e
     let file = get_input(path.to_string());
 
     let module_storage = Arc::new(ModuleStorage::new());
-    let module = Module::new("main".to_string());
+    let module = Module::new("main".to_string(), mosa_fs::module_id(path.as_ref()));
 
-    let mut parser = Parser::new(file, module, module_storage.clone(), workdir.to_string(), "".to_string(), HashMap::new());
+    let resolver = Arc::new(FileSystemResolver::new(workdir));
+    let mut parser = Parser::new(file, module, module_storage.clone(), workdir.to_string(), "".to_string(), resolver);
     let ast = parser.gen_ast();
     let interpreter = Interpreter::new(ast.clone(), module_storage);
 
@@ -1,16 +1,10 @@
-use crate::mosa_fs;
 use colored::Colorize;
 use log::log;
+use std::fs;
 
 pub fn error_stack_traced_parser(message: String, line: usize, column: usize, file_name: String, root: String) {
-    let file = mosa_fs::read_from_path(
-        file_name.clone(),
-        root.clone(),
-        "".to_string(),
-        None
-    );
-
-    let line_text = file.lines().collect::<Vec<&str>>()[line - 1].to_string();
+    // modules of libraries and modules that are not on the disk don't show their source
+    let file = fs::read_to_string(format!("{}{}.mosa", root, file_name)).unwrap_or_default();
 
     println!(
         "{}: {} {} {}: {}:{}",
@@ -21,11 +15,15 @@ pub fn error_stack_traced_parser(message: String, line: usize, column: usize, fi
         line.to_string().bright_yellow(),
         column.to_string().bright_yellow()
     );
-    println!("{}", line_text);
-    println!("{}{}", " ".repeat(column.max(1) - 1), "^".bright_yellow());
+    if let Some(line_text) = file.lines().nth(line.max(1) - 1) {
+        println!("{}", line_text);
+        println!("{}{}", " ".repeat(column.max(1) - 1), "^".bright_yellow());
+    }
 }
 
 pub fn error_interpreter(message: String) {
+    crate::interpreter::exceptions::throw_if_caught(&message);
+
     println!("{}: {}", "[INTERPRETING] [ERROR]".red(), message);
 }
 
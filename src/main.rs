@@ -4,12 +4,15 @@ use crate::wrapper::MoSaRunner;
 
 pub mod global;
 pub mod interpreter;
+pub mod json;
 pub mod lexer;
 pub mod logging;
 pub mod modules;
 pub mod mosa_fs;
 pub mod opts;
+pub mod packages;
 pub mod parser;
+pub mod stdlib;
 pub mod wrapper;
 
 fn main() {
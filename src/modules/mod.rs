@@ -1,34 +1,105 @@
 use crate::interpreter::scope::{
-    FunctionData, RuntimeScope, RuntimeScopeW, ScopeLayoutDeclaration,
+    EnumDefinition, FunctionData, RuntimeScope, RuntimeScopeW, ScopeLayoutDeclaration,
 };
 use crate::interpreter::structs::RuntimeValue;
 use crate::parser::structs::{ASTNode, LayoutDeclaration, ParserFunctionData};
+use indexmap::IndexMap;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
 
+pub mod resolver;
+
+/// Parsed modules keyed by their id, and the graph of their imports.
 pub struct ModuleStorage {
     storage: Arc<RwLock<HashMap<String, Module>>>,
+    /// imports of every module that started parsing, in the order of their `use` statements
+    graph: Arc<RwLock<IndexMap<String, Vec<String>>>>,
 }
 
 impl ModuleStorage {
     pub fn new() -> ModuleStorage {
         ModuleStorage {
             storage: Arc::new(RwLock::new(HashMap::new())),
+            graph: Arc::new(RwLock::new(IndexMap::new())),
         }
     }
 
     pub fn push(&self, module: Module) {
-        if self.get(&module.name).is_none() {
-            // !("Module `{}` is already defined", &module.name)
+        if self.get(&module.id).is_none() {
             self.storage
                 .write()
                 .unwrap()
-                .insert(module.name.clone(), module);
+                .insert(module.id.clone(), module);
         }
     }
 
-    pub fn get(&self, name: &String) -> Option<Module> {
-        self.storage.read().unwrap().get(name).cloned()
+    pub fn get(&self, id: &String) -> Option<Module> {
+        self.storage.read().unwrap().get(id).cloned()
+    }
+
+    /// Marks the module as being parsed.
+    pub fn register(&self, id: String) {
+        self.graph.write().unwrap().entry(id).or_default();
+    }
+
+    /// Returns `true` if the module started parsing, but wasn't pushed yet, so importing it again is a cycle.
+    pub fn is_parsing(&self, id: &String) -> bool {
+        self.graph.read().unwrap().contains_key(id) && self.get(id).is_none()
+    }
+
+    pub fn add_import(&self, from: String, to: String) {
+        let mut graph = self.graph.write().unwrap();
+        let imports = graph.entry(from).or_default();
+
+        if !imports.contains(&to) {
+            imports.push(to);
+        }
+    }
+
+    /// The chain of imports leading from the entry module to `id`.
+    pub fn import_chain(&self, id: &String) -> Vec<String> {
+        let graph = self.graph.read().unwrap();
+        let Some(entry) = graph.keys().next().cloned() else {
+            return vec![id.clone()];
+        };
+
+        let mut chain = vec![entry];
+        let mut visited = HashSet::new();
+
+        if Self::find_chain(&graph, id, &mut chain, &mut visited) {
+            chain
+        } else {
+            vec![id.clone()]
+        }
+    }
+
+    fn find_chain(
+        graph: &IndexMap<String, Vec<String>>,
+        target: &String,
+        chain: &mut Vec<String>,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        let current = chain.last().unwrap().clone();
+
+        if &current == target {
+            return true;
+        }
+
+        if !visited.insert(current.clone()) {
+            return false;
+        }
+
+        for next in graph.get(&current).cloned().unwrap_or_default() {
+            chain.push(next);
+
+            if Self::find_chain(graph, target, chain, visited) {
+                return true;
+            }
+
+            chain.pop();
+        }
+
+        false
     }
 }
 
@@ -36,9 +107,15 @@ impl ModuleStorage {
 pub struct Module {
     unmodulated_exported_functions: Arc<RwLock<HashMap<String, ParserFunctionData>>>,
     unmodulated_exported_layouts: Arc<RwLock<HashMap<String, LayoutDeclaration>>>,
+    /// folded values of the exported constants
+    unmodulated_exported_consts: Arc<RwLock<HashMap<String, ASTNode>>>,
+    /// exported enums, variables, native functions and re-exports, taken from the module scope after it runs
+    unmodulated_exported_symbols: Arc<RwLock<Vec<String>>>,
     exports: Arc<RwLock<HashMap<String, ModuleExport>>>,
     ast: Arc<RwLock<Vec<ASTNode>>>,
     name: String,
+    /// canonical path of the module file
+    id: String,
     pub scope: RuntimeScopeW,
     cached_result: Arc<RwLock<Option<RuntimeValue>>>,
 }
@@ -47,18 +124,25 @@ pub struct Module {
 pub enum ModuleExport {
     Function(FunctionData),
     Layout(Arc<ScopeLayoutDeclaration>),
+    Value(RuntimeValue),
+    Enum(EnumDefinition),
+    /// path of a native function, like `mosa-native~>printLn`
+    NativeFunction(String),
 }
 
 impl Module {
-    pub fn new(name: String) -> Module {
+    pub fn new(name: String, id: String) -> Module {
         Module {
             exports: Arc::new(RwLock::new(HashMap::new())),
             ast: Arc::new(RwLock::new(vec![])),
             name,
+            id,
             scope: RuntimeScope::arc_rwlock_new(None),
             cached_result: Arc::new(RwLock::new(None)),
             unmodulated_exported_functions: Arc::new(RwLock::new(HashMap::new())),
             unmodulated_exported_layouts: Arc::new(RwLock::new(HashMap::new())),
+            unmodulated_exported_consts: Arc::new(RwLock::new(HashMap::new())),
+            unmodulated_exported_symbols: Arc::new(RwLock::new(vec![])),
         }
     }
 
@@ -78,6 +162,10 @@ impl Module {
         self.name.clone()
     }
 
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
     pub fn push(&self, symbol: String, export: ModuleExport) {
         self.exports.write().unwrap().insert(symbol, export);
     }
@@ -96,6 +184,20 @@ impl Module {
             .insert(symbol, lay);
     }
 
+    pub fn push_unmodulated_const(&self, symbol: String, value: ASTNode) {
+        self.unmodulated_exported_consts
+            .write()
+            .unwrap()
+            .insert(symbol, value);
+    }
+
+    pub fn push_unmodulated_symbol(&self, symbol: String) {
+        self.unmodulated_exported_symbols
+            .write()
+            .unwrap()
+            .push(symbol);
+    }
+
     pub fn unmodulated_exported_functions(&self) -> HashMap<String, ParserFunctionData> {
         self.unmodulated_exported_functions.read().unwrap().clone()
     }
@@ -104,6 +206,22 @@ impl Module {
         self.unmodulated_exported_layouts.read().unwrap().clone()
     }
 
+    pub fn unmodulated_exported_consts(&self) -> HashMap<String, ASTNode> {
+        self.unmodulated_exported_consts.read().unwrap().clone()
+    }
+
+    pub fn unmodulated_exported_symbols(&self) -> Vec<String> {
+        self.unmodulated_exported_symbols.read().unwrap().clone()
+    }
+
+    /// Names of all symbols the module exports, known after it was parsed.
+    pub fn exported_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.unmodulated_exported_functions().into_keys().collect();
+        symbols.extend(self.unmodulated_exported_layouts().into_keys());
+        symbols.extend(self.unmodulated_exported_symbols());
+        symbols
+    }
+
     pub fn has_cache(&self) -> bool {
         self.cached_result.read().unwrap().is_some()
     }
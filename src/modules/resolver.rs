@@ -0,0 +1,195 @@
+use crate::mosa_fs;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::PathBuf;
+
+/// A module to look up, e.g. `use test:import~>hi;` in `main.mosa` requests `test/import`,
+/// and `use @std:lib~>std;` requests `lib` of the `std` library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleRequest {
+    pub lib: Option<String>,
+    /// `/`-separated path without the extension, relative to the root or the library
+    pub path: String,
+}
+
+impl Display for ModuleRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.lib {
+            Some(lib) => write!(f, "@{}:{}", lib, self.path.replace('/', ":")),
+            None => write!(f, "{}", self.path.replace('/', ":")),
+        }
+    }
+}
+
+pub struct ResolvedModule {
+    /// the same for every request pointing to the same source
+    pub id: String,
+    pub source: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResolveError {
+    NotFound(ModuleRequest),
+    UnknownLib(String),
+    Read(ModuleRequest, String),
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolveError::NotFound(request) => write!(f, "Cannot find module `{}`.", request),
+            ResolveError::UnknownLib(lib) => write!(f, "Library `{}` is not registered.", lib),
+            ResolveError::Read(request, reason) => {
+                write!(f, "Cannot read module `{}`: {}", request, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Directories listed in the `MOSA_PATH` environment variable.
+pub fn mosa_path() -> Vec<PathBuf> {
+    std::env::var_os("MOSA_PATH")
+        .map(|v| std::env::split_paths(&v).collect())
+        .unwrap_or_default()
+}
+
+/// Finds the source of modules, so scripts can be loaded from the disk, memory or asset packs.
+pub trait ModuleResolver: Send + Sync {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError>;
+}
+
+/// Reads `<root>/<path>.mosa`, or `<lib dir>/<path>.mosa` for libraries.
+/// Modules missing from the root are searched for in the search paths, in order.
+#[derive(Clone, Debug)]
+pub struct FileSystemResolver {
+    root: PathBuf,
+    libs: HashMap<String, PathBuf>,
+    search_paths: Vec<PathBuf>,
+}
+
+impl FileSystemResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            libs: HashMap::new(),
+            search_paths: vec![],
+        }
+    }
+
+    /// Libraries that are not registered are also searched for as directories in the search paths,
+    /// so `@std` can be `<search path>/std`.
+    pub fn add_search_paths(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.search_paths.extend(paths);
+        self
+    }
+
+    pub fn add_lib(mut self, lib: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.libs.insert(lib.into(), path.into());
+        self
+    }
+
+    pub fn add_libs(mut self, libs: HashMap<String, PathBuf>) -> Self {
+        self.libs.extend(libs);
+        self
+    }
+}
+
+impl ModuleResolver for FileSystemResolver {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError> {
+        let bases: Vec<PathBuf> = match &request.lib {
+            Some(lib) => match self.libs.get(lib) {
+                Some(dir) => vec![dir.clone()],
+                None => self.search_paths.iter().map(|p| p.join(lib)).filter(|p| p.is_dir()).collect(),
+            },
+            None => std::iter::once(self.root.clone())
+                .chain(self.search_paths.iter().cloned())
+                .collect(),
+        };
+
+        if bases.is_empty() {
+            return Err(ResolveError::UnknownLib(request.lib.clone().unwrap_or_default()));
+        }
+
+        // absolute paths come from `use $"..."`
+        let file = bases
+            .iter()
+            .map(|base| base.join(format!("{}.mosa", request.path)))
+            .find(|file| file.is_file())
+            .ok_or_else(|| ResolveError::NotFound(request.clone()))?;
+
+        let source = fs::read_to_string(&file)
+            .map_err(|e| ResolveError::Read(request.clone(), e.to_string()))?;
+
+        Ok(ResolvedModule {
+            id: mosa_fs::module_id(&file),
+            source,
+        })
+    }
+}
+
+/// Modules kept in memory, keyed by their path, or by `@lib:path` for libraries.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryResolver {
+    modules: HashMap<String, String>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `path` is written like in `use`, e.g. `test:import` or `@std:lib`.
+    pub fn add_module(mut self, path: impl Into<String>, source: impl Into<String>) -> Self {
+        self.modules.insert(path.into(), source.into());
+        self
+    }
+
+    pub fn insert(&mut self, path: impl Into<String>, source: impl Into<String>) {
+        self.modules.insert(path.into(), source.into());
+    }
+}
+
+impl ModuleResolver for MemoryResolver {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError> {
+        let key = request.to_string();
+
+        match self.modules.get(&key) {
+            Some(source) => Ok(ResolvedModule {
+                id: format!("memory:{}", key),
+                source: source.clone(),
+            }),
+            None => Err(ResolveError::NotFound(request.clone())),
+        }
+    }
+}
+
+/// Modules compiled into the binary, e.g.
+/// `StaticResolver::new(&[("main", include_str!("main.mosa")), ("@std:lib", include_str!("std.mosa"))])`.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticResolver {
+    modules: &'static [(&'static str, &'static str)],
+}
+
+impl StaticResolver {
+    pub const fn new(modules: &'static [(&'static str, &'static str)]) -> Self {
+        Self { modules }
+    }
+}
+
+impl ModuleResolver for StaticResolver {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError> {
+        let key = request.to_string();
+
+        self.modules
+            .iter()
+            .find(|(path, _)| *path == key)
+            .map(|(_, source)| ResolvedModule {
+                id: format!("static:{}", key),
+                source: source.to_string(),
+            })
+            .ok_or_else(|| ResolveError::NotFound(request.clone()))
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const LOCKFILE_NAME: &str = "mosa.lock";
+
+/// `mosa.lock`, the exact versions chosen for every package, so the next resolution picks the same.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// `registry`, or `path+<dir>` for path dependencies
+    pub source: String,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    /// A missing or unreadable lockfile is the same as an empty one.
+    pub fn read(dir: &Path) -> Self {
+        fs::read_to_string(dir.join(LOCKFILE_NAME))
+            .ok()
+            .and_then(|source| toml::from_str(&source).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write(&self, dir: &Path) -> anyhow::Result<()> {
+        let source = format!(
+            "# Generated by MoSa, do not edit by hand.\n\n{}",
+            toml::to_string(self)?
+        );
+
+        // rewriting an unchanged lockfile would only touch its modification time
+        if fs::read_to_string(dir.join(LOCKFILE_NAME)).ok().as_deref() != Some(source.as_str()) {
+            fs::write(dir.join(LOCKFILE_NAME), source)?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the lockfile of `dir`, if there is one.
+    pub fn remove(dir: &Path) -> anyhow::Result<()> {
+        match fs::remove_file(dir.join(LOCKFILE_NAME)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+}
@@ -0,0 +1,183 @@
+pub mod lockfile;
+pub mod registry;
+
+use crate::packages::lockfile::{LockedPackage, Lockfile};
+use crate::packages::registry::Registry;
+use crate::wrapper::manifest::{Dependency, Manifest};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Registry packages are unpacked into `<project>/.mosa/packages`.
+pub const PACKAGES_DIR: &str = ".mosa/packages";
+
+#[derive(Clone, Debug)]
+pub struct ResolvedPackage {
+    pub name: String,
+    pub version: Version,
+    /// the directory holding the package's `mosa.toml`
+    pub root: PathBuf,
+    /// the directory `use @<name>:...` reads modules from
+    pub lib: PathBuf,
+    /// `None` for packages from the registry
+    pub path: Option<PathBuf>,
+    pub dependencies: Vec<String>,
+}
+
+/// Resolves the dependencies of the manifest at `manifest_path` and all of theirs, unpacks registry
+/// packages and writes `mosa.lock` next to the manifest, or removes it if there are no dependencies.
+/// Every package can only be used in one version, conflicting requirements are an error.
+pub fn install(manifest_path: &Path, manifest: &Manifest) -> anyhow::Result<Vec<ResolvedPackage>> {
+    let project_dir = manifest_path.parent().unwrap_or(Path::new("."));
+
+    // projects without dependencies don't need a lockfile, one left from removed dependencies is deleted
+    if manifest.dependencies.is_empty() {
+        Lockfile::remove(project_dir)?;
+        return Ok(vec![]);
+    }
+
+    let lockfile = Lockfile::read(project_dir);
+    let registry = manifest.registry.as_ref().map(|r| Registry::new(&r.path));
+
+    let mut resolved: BTreeMap<String, ResolvedPackage> = BTreeMap::new();
+    let mut queue: VecDeque<(String, Dependency, String)> = manifest
+        .dependencies
+        .iter()
+        .map(|(name, dep)| (name.clone(), dep.clone(), "the project".to_string()))
+        .collect();
+
+    while let Some((name, dependency, required_by)) = queue.pop_front() {
+        let req = match dependency.version() {
+            Some(v) => Some(VersionReq::parse(v).map_err(|e| {
+                anyhow::anyhow!("Invalid version `{}` of `{}` in {}: {}", v, name, required_by, e)
+            })?),
+            None => None,
+        };
+
+        if let Some(existing) = resolved.get(&name) {
+            check_compatible(existing, &dependency, req.as_ref(), &required_by)?;
+            continue;
+        }
+
+        let (root, path) = match dependency.path() {
+            Some(path) => (path.to_path_buf(), Some(path.to_path_buf())),
+            None => {
+                let Some(req) = &req else {
+                    anyhow::bail!("Dependency `{}` of {} needs a version or a path.", name, required_by);
+                };
+
+                let Some(registry) = &registry else {
+                    anyhow::bail!(
+                        "Dependency `{}` of {} comes from the registry, but the project has no [registry].",
+                        name,
+                        required_by
+                    );
+                };
+
+                let locked = lockfile
+                    .get(&name)
+                    .filter(|l| l.source == "registry")
+                    .and_then(|l| Version::parse(&l.version).ok());
+
+                let version = registry.select(&name, req, locked.as_ref())?;
+
+                (registry.unpack(&name, &version, &project_dir.join(PACKAGES_DIR))?, None)
+            }
+        };
+
+        let package_manifest = Manifest::load(root.join("mosa.toml"))?;
+
+        let Some(package) = package_manifest.package else {
+            anyhow::bail!("`{}` is not a package, its manifest has no [package] table.", root.display());
+        };
+
+        if package.name != name {
+            anyhow::bail!(
+                "{} depends on `{}`, but `{}` contains package `{}`.",
+                required_by,
+                name,
+                root.display(),
+                package.name
+            );
+        }
+
+        let version = Version::parse(&package.version)?;
+
+        if let Some(req) = req.as_ref().filter(|r| !r.matches(&version)) {
+            anyhow::bail!("{} requires `{}` {}, but found {}.", required_by, name, req, version);
+        }
+
+        let requester = format!("`{}` {}", name, version);
+
+        for (dep_name, dep) in &package_manifest.dependencies {
+            queue.push_back((dep_name.clone(), dep.clone(), requester.clone()));
+        }
+
+        resolved.insert(
+            name.clone(),
+            ResolvedPackage {
+                name,
+                version,
+                root,
+                lib: package.lib,
+                path,
+                dependencies: package_manifest.dependencies.keys().cloned().collect(),
+            },
+        );
+    }
+
+    let lockfile = Lockfile {
+        packages: resolved
+            .values()
+            .map(|p| LockedPackage {
+                name: p.name.clone(),
+                version: p.version.to_string(),
+                source: match &p.path {
+                    Some(path) => format!(
+                        "path+{}",
+                        path.strip_prefix(project_dir).unwrap_or(path).display()
+                    ),
+                    None => "registry".to_string(),
+                },
+                dependencies: p.dependencies.clone(),
+            })
+            .collect(),
+    };
+
+    lockfile.write(project_dir)?;
+
+    Ok(resolved.into_values().collect())
+}
+
+fn check_compatible(
+    existing: &ResolvedPackage,
+    dependency: &Dependency,
+    req: Option<&VersionReq>,
+    required_by: &str,
+) -> anyhow::Result<()> {
+    if let Some(req) = req.filter(|r| !r.matches(&existing.version)) {
+        anyhow::bail!(
+            "{} requires `{}` {}, but {} is already used.",
+            required_by,
+            existing.name,
+            req,
+            existing.version
+        );
+    }
+
+    let same_source = match (dependency.path(), &existing.path) {
+        (Some(a), Some(b)) => a.canonicalize().ok() == b.canonicalize().ok(),
+        (None, None) => true,
+        _ => false,
+    };
+
+    if !same_source {
+        anyhow::bail!(
+            "{} uses `{}` from a different source than the other packages.",
+            required_by,
+            existing.name
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::wrapper::manifest::Manifest;
+use semver::{Version, VersionReq};
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// A local directory of package archives named `<name>-<version>.tar`, each with a `mosa.toml`
+/// at its root.
+#[derive(Clone, Debug)]
+pub struct Registry {
+    dir: PathBuf,
+}
+
+impl Registry {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// All published versions of a package, oldest first.
+    pub fn versions(&self, name: &str) -> anyhow::Result<Vec<Version>> {
+        let entries = fs::read_dir(&self.dir).map_err(|e| {
+            anyhow::anyhow!("Cannot read registry `{}`: {}", self.dir.display(), e)
+        })?;
+
+        let mut versions: Vec<Version> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let version = file_name
+                    .strip_suffix(".tar")?
+                    .strip_prefix(name)?
+                    .strip_prefix('-')?;
+
+                Version::parse(version).ok()
+            })
+            .collect();
+
+        versions.sort();
+
+        Ok(versions)
+    }
+
+    /// The newest version matching `req`, or `preferred` if it matches and is published.
+    pub fn select(
+        &self,
+        name: &str,
+        req: &VersionReq,
+        preferred: Option<&Version>,
+    ) -> anyhow::Result<Version> {
+        let versions = self.versions(name)?;
+
+        if let Some(preferred) = preferred.filter(|v| req.matches(v) && versions.contains(v)) {
+            return Ok(preferred.clone());
+        }
+
+        versions
+            .into_iter()
+            .rev()
+            .find(|v| req.matches(v))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No version of `{}` in registry `{}` matches `{}`.",
+                    name,
+                    self.dir.display(),
+                    req
+                )
+            })
+    }
+
+    pub fn archive(&self, name: &str, version: &Version) -> PathBuf {
+        self.dir.join(format!("{}-{}.tar", name, version))
+    }
+
+    /// Extracts a package into `<target>/<name>-<version>`, unless it already is.
+    pub fn unpack(&self, name: &str, version: &Version, target: &Path) -> anyhow::Result<PathBuf> {
+        let dir = target.join(format!("{}-{}", name, version));
+
+        if dir.join("mosa.toml").is_file() {
+            return Ok(dir);
+        }
+
+        let archive = self.archive(name, version);
+        let file = File::open(&archive)
+            .map_err(|e| anyhow::anyhow!("Cannot open `{}`: {}", archive.display(), e))?;
+
+        fs::create_dir_all(&dir)?;
+        tar::Archive::new(file).unpack(&dir)?;
+
+        Ok(dir)
+    }
+
+    /// Archives the package of `manifest_path` into the registry, skipping installed packages.
+    pub fn publish(&self, manifest_path: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+        let manifest_path = manifest_path.as_ref();
+        let manifest = Manifest::load(manifest_path)?;
+
+        let Some(package) = manifest.package else {
+            anyhow::bail!("Manifest `{}` has no [package] table.", manifest_path.display());
+        };
+
+        let version = Version::parse(&package.version)?;
+        let archive = self.archive(&package.name, &version);
+
+        if archive.exists() {
+            anyhow::bail!("`{}` {} is already published.", package.name, version);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+
+        let root = manifest_path.parent().unwrap_or(Path::new("."));
+        let mut builder = tar::Builder::new(File::create(&archive)?);
+
+        append_package_files(&mut builder, root, Path::new(""))?;
+
+        builder.finish()?;
+
+        Ok(archive)
+    }
+}
+
+fn append_package_files(
+    builder: &mut tar::Builder<File>,
+    root: &Path,
+    relative: &Path,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        let name = entry.file_name();
+
+        if name == ".mosa" || name == super::lockfile::LOCKFILE_NAME {
+            continue;
+        }
+
+        let path = relative.join(&name);
+
+        if entry.file_type()?.is_dir() {
+            append_package_files(builder, root, &path)?;
+        } else {
+            builder.append_path_with_name(root.join(&path), &path)?;
+        }
+    }
+
+    Ok(())
+}
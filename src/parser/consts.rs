@@ -0,0 +1,138 @@
+use crate::err;
+use crate::global::DataType;
+use crate::interpreter::structs::RuntimeValue;
+use crate::lexer::structs::{OperatorType, SignType, TokenValue};
+use crate::parser::structs::{ASTNode, ExpressionType, Operand};
+use crate::parser::Parser;
+
+impl Parser {
+    /// Parses `const NAME [: type] = <expr>`, the value is folded into a literal, and every later
+    /// use of `NAME` is replaced with it.
+    pub(super) fn parse_const_declaration(&mut self) -> ASTNode {
+        self.go(); // `const`
+
+        let tk = self.go();
+        let TokenValue::Identifier(identifier) = tk.value.clone() else {
+            err!(ft tk.clone(), self.root.clone(), "Expected an identifier after `const`.");
+            self.set_end();
+            return ASTNode::InternalStop(tk.line, tk.file_name);
+        };
+
+        if self.consts.contains_key(&identifier) {
+            err!(ft tk.clone(), self.root.clone(), "Constant `{}` is already declared.", identifier);
+            self.set_end();
+            return ASTNode::InternalStop(tk.line, tk.file_name);
+        }
+
+        let mut data_type = DataType::InternalInfer;
+
+        if self.curr().value == TokenValue::Sign(SignType::Colon) {
+            self.go();
+
+            data_type = self.parse_data_type();
+        }
+
+        if self.go().value != TokenValue::Operator(OperatorType::Equal) {
+            err!(ft self.last(), self.root.clone(), "Declaring constants requires a value.");
+            self.set_end();
+            return ASTNode::InternalStop(tk.line, tk.file_name);
+        }
+
+        let expr = self.parse_expressions();
+
+        match fold_const(&expr) {
+            Ok(folded) => {
+                self.consts.insert(identifier.clone(), folded.clone());
+
+                ASTNode::VariableDeclaration(true, identifier, data_type, Box::new(folded))
+            }
+            Err(reason) => {
+                err!(ft tk.clone(), self.root.clone(), "The value of `{}` is not a constant expression: {}", identifier, reason);
+                self.set_end();
+                ASTNode::InternalStop(tk.line, tk.file_name)
+            }
+        }
+    }
+
+    /// Returns the folded value if `identifier` names a constant visible at this point.
+    pub(super) fn get_const(&self, identifier: &String) -> Option<ASTNode> {
+        self.consts.get(identifier).cloned()
+    }
+}
+
+/// Evaluates literals, arithmetic, comparisons and enum entries. Constants used in the expression
+/// have already been replaced with their values while parsing.
+pub fn fold_const(node: &ASTNode) -> Result<ASTNode, String> {
+    match node {
+        ASTNode::Number(_) | ASTNode::String(_) | ASTNode::Boolean(_) => Ok(node.clone()),
+        ASTNode::ComplexTypeAccessor(_, _) => Ok(node.clone()),
+        ASTNode::Expression(ExpressionType::Binary(expr)) => {
+            let left = fold_const(&expr.left)?;
+            let right = fold_const(&expr.right)?;
+
+            if let (
+                ASTNode::ComplexTypeAccessor(le, lv),
+                ASTNode::ComplexTypeAccessor(re, rv),
+            ) = (&left, &right)
+            {
+                let equal = le == re && lv == rv;
+                return match expr.operand {
+                    Operand::Equality => Ok(ASTNode::Boolean(equal)),
+                    Operand::Inequality => Ok(ASTNode::Boolean(!equal)),
+                    _ => Err("enum entries can only be compared".to_string()),
+                };
+            }
+
+            let (Some(l), Some(r)) = (literal_value(&left), literal_value(&right)) else {
+                return Err(format!("{} cannot be combined with {}", kind(&left), kind(&right)));
+            };
+
+            let res = match expr.operand {
+                Operand::Plus => l + r,
+                Operand::Minus => l - r,
+                Operand::Multiply => l * r,
+                Operand::Divide => l / r,
+                Operand::Equality => RuntimeValue::Bool(l == r),
+                Operand::Inequality => RuntimeValue::Bool(l != r),
+                Operand::Bigger => l.bigger(&r, false),
+                Operand::Smaller => l.smaller(&r, false),
+                Operand::BiggerEqual => l.bigger(&r, true),
+                Operand::SmallerEqual => l.smaller(&r, true),
+                _ => return Err(format!("operator `{:?}` cannot be used in constants", expr.operand)),
+            };
+
+            value_literal(&res).ok_or_else(|| "the operand types do not match".to_string())
+        }
+        ASTNode::Identifier(v) => Err(format!("`{}` is not a constant", v)),
+        _ => Err("only literals, arithmetic, other constants and enum entries are allowed".to_string()),
+    }
+}
+
+/// What a folded operand is, for errors.
+fn kind(node: &ASTNode) -> &'static str {
+    match node {
+        ASTNode::Number(_) => "a number",
+        ASTNode::String(_) => "a string",
+        ASTNode::Boolean(_) => "a boolean",
+        ASTNode::ComplexTypeAccessor(_, _) => "an enum entry",
+        _ => "a value",
+    }
+}
+
+fn literal_value(node: &ASTNode) -> Option<RuntimeValue> {
+    match node {
+        ASTNode::Number(v) => Some(RuntimeValue::Number(*v)),
+        ASTNode::String(v) => Some(RuntimeValue::String(v.clone())),
+        ASTNode::Boolean(v) => Some(RuntimeValue::Bool(*v)),
+        _ => None,
+    }
+}
+
+fn value_literal(value: &RuntimeValue) -> Option<ASTNode> {
+    match value {
+        RuntimeValue::Number(v) => Some(ASTNode::Number(*v)),
+        RuntimeValue::String(v) => Some(ASTNode::String(v.clone())),
+        RuntimeValue::Bool(v) => Some(ASTNode::Boolean(*v)),
+        _ => None,
+    }
+}
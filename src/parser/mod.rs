@@ -5,23 +5,26 @@ use crate::interpreter::scope::{FunctionData, ScopeLayoutDeclaration};
 use crate::lexer::structs::{Direction, KeywordType, OperatorType, SignType, Token, TokenValue};
 use crate::lexer::tokenize;
 use crate::modules::ModuleExport;
+use crate::modules::resolver::{ModuleRequest, ModuleResolver};
 use crate::modules::{Module, ModuleStorage};
-use crate::mosa_fs;
-use crate::parser::structs::{ModulePathMode, OnceStatement};
+use crate::parser::structs::{ModuleImport, OnceStatement};
 use crate::parser::structs::UseNative;
 use crate::parser::structs::{
     ASTNode, AssignmentProperty, BinaryExpression, ExpressionType, IfStatement, LayoutCreation,
     Operand,
 };
-use crate::parser::structs::{FieldParserDescription, LayoutDeclaration};
-use crate::parser::structs::{ForStatement, ParserFunctionData};
+use crate::parser::structs::{
+    ClassDeclaration, Derive, FieldParserDescription, LayoutDeclaration,
+};
+use crate::parser::structs::{ForStatement, ParserFunctionData, RangeExpression};
+use crate::parser::structs::{CatchClause, TryStatement};
 use crate::{err, logging};
 use indexmap::IndexMap;
-use std::collections::{HashMap, VecDeque};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::process::exit;
 use std::sync::{Arc, RwLock};
 
+pub mod consts;
 pub mod structs;
 
 #[derive(Clone)]
@@ -33,7 +36,24 @@ pub struct Parser {
     root: String,
     relative_root: String,
     should_end: bool,
-    libs: HashMap<String, PathBuf>
+    resolver: Arc<dyn ModuleResolver>,
+    /// library this module belongs to, its imports are resolved inside of it
+    lib: Option<String>,
+    /// folded values of the constants declared so far
+    consts: HashMap<String, ASTNode>,
+    /// modules imported with `use path as namespace;`
+    namespaces: HashSet<String>,
+    /// set while parsing the head of `for` and `if`, where `name {` starts the block and not a layout,
+    /// parens and call arguments clear it again
+    in_statement_head: bool,
+    /// set while parsing the body of a `gen fn`, the only place where `yield` can be used
+    in_generator: bool,
+    /// set right before a statement of a code block, `yield` has to start a statement
+    at_statement: bool,
+    /// set while parsing the blocks of `try`, a generator cannot stop inside of them
+    in_try: bool,
+    /// set while parsing the body of a function, `?` returns from it
+    in_function: bool,
 }
 
 impl Parser {
@@ -51,10 +71,12 @@ impl Parser {
         module_storage: Arc<ModuleStorage>,
         root: String,
         path: String,
-        libs: HashMap<String, PathBuf>,
+        resolver: Arc<dyn ModuleResolver>,
     ) -> Self {
         let tokens = tokenize(module.name(), source);
 
+        module_storage.register(module.id());
+
         // dbg!(&tokens);
 
         Self {
@@ -65,7 +87,15 @@ impl Parser {
             root,
             relative_root: path,
             should_end: false,
-            libs
+            resolver,
+            lib: None,
+            consts: HashMap::new(),
+            namespaces: HashSet::new(),
+            in_statement_head: false,
+            in_generator: false,
+            at_statement: false,
+            in_try: false,
+            in_function: false,
         }
     }
 
@@ -143,12 +173,22 @@ impl Parser {
 
     fn parse_expressions(&mut self) -> ASTNode {
         // dbg!(&&&self.curr());
+        let at_statement = std::mem::replace(&mut self.at_statement, false);
+
         match self.curr().value {
             TokenValue::Keyword(keyword) => match keyword {
                 KeywordType::Let => self.parse_variable_declaration(),
-                KeywordType::Const => self.parse_variable_declaration(),
+                KeywordType::Const => self.parse_const_declaration(),
                 KeywordType::Immut => self.parse_variable_declaration(),
-                KeywordType::Fn => self.parse_fn_declaration(),
+                KeywordType::Fn | KeywordType::Gen => self.parse_fn_declaration(),
+                KeywordType::Yield => self.parse_yield(at_statement),
+                KeywordType::Throw => {
+                    self.go(); // `throw`
+
+                    ASTNode::Throw(Box::new(self.parse_expressions()))
+                }
+                KeywordType::Try => self.parse_try_statement(),
+                KeywordType::Ok | KeywordType::Err => self.parse_start_expr(),
                 KeywordType::If => self.parse_if_declaration(),
                 KeywordType::Once => self.parse_once_declaration(),
                 KeywordType::Use => self.parse_use(),
@@ -166,8 +206,10 @@ impl Parser {
                 }
                 KeywordType::Layout => self.parse_layout_declaration(),
                 KeywordType::Mix => self.parse_mix(None),
+                KeywordType::Expand => self.parse_expand(),
                 KeywordType::Exp => self.parse_exp(),
                 KeywordType::Repeat => self.parse_repeat_expression(),
+                KeywordType::Class => self.parse_class_declaration(),
                 _ => ASTNode::Expression(ExpressionType::Null),
             },
             TokenValue::Identifier(_) => self.parse_start_expr(),
@@ -199,7 +241,9 @@ impl Parser {
                         if *direction == Direction::Open {
                             // dbg!("?");
                             self.go();
+                            let in_statement_head = std::mem::replace(&mut self.in_statement_head, false);
                             let v = self.parse_expressions();
+                            self.in_statement_head = in_statement_head;
                             self.expect_token(TokenValue::Sign(SignType::Paren(Direction::Close)), "Expected a closing paren.");
                             // dbg!(&self.curr());
                             v
@@ -210,7 +254,7 @@ impl Parser {
                     }
                     SignType::DoubleColon => {
                         self.go();
-                        match self.parse_fn_lower("MOSA_INTERNAL_LAMBDA".to_string()) {
+                        match self.parse_fn_lower("MOSA_INTERNAL_LAMBDA".to_string(), false) {
                             ASTNode::FunctionDeclaration(_n, a, b, r) => ASTNode::Lambda(a, b, r),
                             _ => unreachable!(),
                         }
@@ -229,6 +273,18 @@ impl Parser {
                     _ => null
                 }
             },
+            TokenValue::Keyword(keyword @ (KeywordType::Ok | KeywordType::Err)) => {
+                self.go(); // `Ok` or `Err`
+
+                self.expect_token(
+                    TokenValue::Sign(SignType::Paren(Direction::Open)),
+                    &format!("Expected an opening paren after `{:?}`.", keyword),
+                );
+                let value = self.parse_expressions();
+                self.expect_token(TokenValue::Sign(SignType::Paren(Direction::Close)), "Expected a closing paren.");
+
+                ASTNode::ResultCreation(*keyword == KeywordType::Ok, Box::new(value))
+            }
             TokenValue::String(v) => {
                 self.go();
                 ASTNode::String(v.clone())
@@ -239,9 +295,21 @@ impl Parser {
             }
             TokenValue::Identifier(v) => {
                 self.go();
-                if self.curr().value == TokenValue::Sign(SignType::Arrow) {
+                let v = &self.parse_namespaced_identifier(v.clone());
+
+                if let Some(folded) = self.get_const(v) {
+                    if self.curr().value == TokenValue::Operator(OperatorType::Equal) {
+                        err!(ft token.clone(), self.root.clone(), "Cannot assign to `{}`, as it's a constant.", v);
+                        self.set_end();
+                        return ASTNode::InternalStop(token.line, token.file_name);
+                    }
+
+                    folded
+                } else if self.curr().value == TokenValue::Sign(SignType::Arrow) {
                     self.parse_complex_type_access(&v)
-                } else if self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Open)) {
+                } else if self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Open))
+                    && !self.in_statement_head
+                {
                     self.parse_layout_creation(&v)
                 } else {
                     ASTNode::Identifier(v.clone())
@@ -251,33 +319,86 @@ impl Parser {
         }
     }
 
+    /// Joins `namespace::symbol` into a single identifier, as namespaced symbols are imported with that name.
+    fn parse_namespaced_identifier(&mut self, identifier: String) -> String {
+        if !self.namespaces.contains(&identifier)
+            || self.curr().value != TokenValue::Sign(SignType::DoubleColon)
+        {
+            return identifier;
+        }
+
+        self.go(); // `::`
+
+        if let TokenValue::Identifier(symbol) = self.go().value {
+            format!("{}::{}", identifier, symbol)
+        } else {
+            err!(ft self.last(), self.root.clone(), "Expected a symbol after `{}::`.", identifier);
+            self.set_end();
+            identifier
+        }
+    }
+
     fn parse_start_expr(&mut self) -> ASTNode {
         self.parse_variable_assignment()
     }
 
-    fn parse_double_dot_expressions(&mut self) -> ASTNode {
-        let mut left = self.parse_add_expressions();
-        let token = self.curr();
+    /// DAS, `a ->> f` calls `f(a)` and `a ->> f(b)` calls `f(a, b)`.
+    fn parse_das_expressions(&mut self) -> ASTNode {
+        let mut left = self.parse_range_expressions();
 
-        while token.value == TokenValue::Sign(SignType::DoubleDot) {
-            let operator = self.curr();
-            if operator.value != TokenValue::Sign(SignType::DoubleDot) {
-                break;
-            }
-            self.go();
-            let operand = Operand::DoubleDot;
-            let right = self.parse_add_expressions();
+        while self.curr().value == TokenValue::Sign(SignType::DoubleArrow) && !self.is_end() {
+            let arrow = self.go(); // ->>
 
-            left = ASTNode::Expression(ExpressionType::Binary(Box::new(BinaryExpression {
-                left: Box::new(left),
-                right: Box::new(right),
-                operand,
-            })))
+            left = match self.parse_call() {
+                ASTNode::FunctionCall(function, mut args) => {
+                    args.insert(0, left);
+                    ASTNode::FunctionCall(function, args)
+                }
+                ASTNode::Expression(ExpressionType::Null) => {
+                    err!(ft arrow.clone(), self.root.clone(), "Expected a function after `->>`.");
+                    self.set_end();
+                    return ASTNode::InternalStop(arrow.line, arrow.file_name);
+                }
+                function => ASTNode::FunctionCall(Box::new(function), vec![left]),
+            };
         }
 
         left
     }
 
+    /// `start..end`, `start..=end` and both followed by `step n`, `step` is only a keyword after a range.
+    fn parse_range_expressions(&mut self) -> ASTNode {
+        let start = self.parse_add_expressions();
+
+        if self.curr().value != TokenValue::Sign(SignType::DoubleDot) {
+            return start;
+        }
+
+        self.go(); // ..
+
+        let inclusive = self.curr().value == TokenValue::Operator(OperatorType::Equal);
+
+        if inclusive {
+            self.go(); // =
+        }
+
+        let end = self.parse_add_expressions();
+
+        let step = if self.curr().value == TokenValue::Identifier("step".to_string()) {
+            self.go(); // step
+            Some(Box::new(self.parse_add_expressions()))
+        } else {
+            None
+        };
+
+        ASTNode::Range(RangeExpression {
+            start: Box::new(start),
+            end: Box::new(end),
+            step,
+            inclusive,
+        })
+    }
+
     fn parse_add_expressions(&mut self) -> ASTNode {
         let mut left = self.parse_multiply_expressions();
         let token = self.curr();
@@ -336,6 +457,12 @@ impl Parser {
             return ASTNode::InternalStop(tk.line, tk.file_name);
         }
 
+        if self.consts.contains_key(&identifier) {
+            err!(ft self.last(), self.root.clone(), "Cannot declare `{}`, as it's already declared as a constant.", identifier);
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
+        }
+
         let mut data_type = DataType::InternalInfer;
 
         if self.curr().value == TokenValue::Sign(SignType::Colon) {
@@ -427,7 +554,7 @@ impl Parser {
     }
 
     fn parse_comparison_expressions(&mut self) -> ASTNode {
-        let mut left = self.parse_layout_property_access();
+        let mut left = self.parse_question_mark_expressions();
         let token = self.curr();
 
         while token.value == TokenValue::Operator(OperatorType::Bigger)
@@ -454,7 +581,7 @@ impl Parser {
                 },
                 _ => unreachable!(),
             };
-            let right = self.parse_layout_property_access();
+            let right = self.parse_question_mark_expressions();
 
             left = ASTNode::Expression(ExpressionType::Binary(Box::new(BinaryExpression {
                 left: Box::new(left),
@@ -466,6 +593,32 @@ impl Parser {
         left
     }
 
+    /// `expr?` gives the value of an `Ok`, and returns an `Err` from the function right away.
+    /// It's postfix, so the right side of the expression is empty.
+    fn parse_question_mark_expressions(&mut self) -> ASTNode {
+        let mut expr = self.parse_layout_property_access();
+
+        while self.curr().value == TokenValue::Sign(SignType::QuestionMk) {
+            let token = self.go(); // `?`
+
+            if !self.in_function || self.in_generator {
+                let place = if self.in_generator { "a `gen fn`" } else { "code outside of functions" };
+
+                err!(ft token.clone(), self.root.clone(), "`?` cannot be used in {}, as there is no function to return from.", place);
+                self.set_end();
+                return ASTNode::InternalStop(token.line, token.file_name);
+            }
+
+            expr = ASTNode::Expression(ExpressionType::Binary(Box::new(BinaryExpression {
+                left: Box::new(expr),
+                right: Box::new(ASTNode::Expression(ExpressionType::Null)),
+                operand: Operand::QuestionMk,
+            })));
+        }
+
+        expr
+    }
+
     fn parse_variable_assignment(&mut self) -> ASTNode {
         // let identifier_token = self.go();
         // let _ = self.go(); // equals sign goes here
@@ -477,7 +630,7 @@ impl Parser {
         //     unreachable!()
         // }
 
-        let mut left = self.parse_double_dot_expressions();
+        let mut left = self.parse_das_expressions();
         let token = self.curr();
 
         while token.value == TokenValue::Operator(OperatorType::Equal) {
@@ -486,7 +639,7 @@ impl Parser {
                 break;
             }
             self.go();
-            let right = self.parse_double_dot_expressions();
+            let right = self.parse_das_expressions();
 
             left = ASTNode::Assignment(self.get_assignment_property(&left), Box::new(right))
         }
@@ -523,12 +676,24 @@ impl Parser {
     }
 
     fn parse_fn_declaration(&mut self) -> ASTNode {
+        let generator = self.curr().value == TokenValue::Keyword(KeywordType::Gen);
+
+        if generator {
+            self.go(); // gen keyword
+
+            if self.curr().value != TokenValue::Keyword(KeywordType::Fn) {
+                err!(ft self.curr(), self.root.clone(), "Expecting the `fn` keyword after `gen`.");
+                self.set_end();
+                return ASTNode::InternalStop(self.curr().line, self.curr().file_name);
+            }
+        }
+
         self.go(); // fn keyword
         let identifier_token = self.curr();
         //dbg!(&&&&&&&&&&&&identifier_token);
         if let TokenValue::Identifier(identifier) = identifier_token.value {
             self.go(); // identifier
-            self.parse_fn_lower(identifier)
+            self.parse_fn_lower(identifier, generator)
         } else {
             err!(
                 self.curr().file_name,
@@ -542,7 +707,8 @@ impl Parser {
         }
     }
 
-    fn parse_fn_lower(&mut self, identifier: String) -> ASTNode {
+    /// A `gen fn` gives an `iterable` of the type after the arrow, its body is wrapped in `ASTNode::Generator`.
+    fn parse_fn_lower(&mut self, identifier: String, generator: bool) -> ASTNode {
         if self.curr().value == TokenValue::Sign(SignType::Paren(Direction::Open)) {
             let args_list = self.parse_fn_args_list();
 
@@ -563,7 +729,26 @@ impl Parser {
                 "Expected a code block.",
             );
 
+            let in_generator = std::mem::replace(&mut self.in_generator, generator);
+            let in_try = std::mem::replace(&mut self.in_try, false);
+            let in_function = std::mem::replace(&mut self.in_function, true);
             let body = self.parse_code_block();
+            self.in_generator = in_generator;
+            self.in_try = in_try;
+            self.in_function = in_function;
+
+            if generator {
+                let ASTNode::CodeBlock(code) = body else {
+                    unreachable!()
+                };
+
+                return ASTNode::FunctionDeclaration(
+                    identifier,
+                    args_list,
+                    Box::new(ASTNode::CodeBlock(vec![ASTNode::Generator(code, data_type.clone())])),
+                    DataType::Primitive(PrimitiveDataType::Iterable(Box::new(data_type))),
+                );
+            }
 
             ASTNode::FunctionDeclaration(identifier, args_list, Box::new(body), data_type)
         } else {
@@ -579,6 +764,30 @@ impl Parser {
         }
     }
 
+    fn parse_yield(&mut self, at_statement: bool) -> ASTNode {
+        let token = self.go(); // yield
+
+        if !self.in_generator {
+            err!(ft token.clone(), self.root.clone(), "`yield` can only be used in the body of a `gen fn`.");
+            self.set_end();
+            return ASTNode::InternalStop(token.line, token.file_name);
+        }
+
+        if !at_statement {
+            err!(ft token.clone(), self.root.clone(), "`yield` has to start a statement, e.g. `yield value;`.");
+            self.set_end();
+            return ASTNode::InternalStop(token.line, token.file_name);
+        }
+
+        if self.in_try {
+            err!(ft token.clone(), self.root.clone(), "`yield` cannot be used inside of `try`, `catch` and `finally`.");
+            self.set_end();
+            return ASTNode::InternalStop(token.line, token.file_name);
+        }
+
+        ASTNode::Yield(Box::new(self.parse_expressions()))
+    }
+
     fn parse_fn_args_list(&mut self) -> IndexMap<String, DataType> {
         self.go(); // paren
         let mut args_map: IndexMap<String, DataType> = IndexMap::new();
@@ -597,6 +806,11 @@ impl Parser {
         let identifier_token = self.go();
         // //dbg!(identifier_token.clone());
         if let TokenValue::Identifier(identifier) = identifier_token.value {
+            if self.consts.contains_key(&identifier) {
+                err!(ft self.last(), self.root.clone(), "Argument `{}` has the same name as a constant.", identifier);
+                self.set_end();
+            }
+
             if self.curr().value == TokenValue::Sign(SignType::Colon) {
                 self.go(); // colon
 
@@ -637,6 +851,9 @@ impl Parser {
 
     fn parse_code_block(&mut self) -> ASTNode {
         let mut nodes: Vec<ASTNode> = Vec::new();
+        // constants declared inside of the block are not visible after it
+        let outer_consts = self.consts.clone();
+        let in_statement_head = std::mem::replace(&mut self.in_statement_head, false);
 
         if self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Open)) {
             self.go();
@@ -645,10 +862,14 @@ impl Parser {
         while self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
             && !self.is_end()
         {
+            self.at_statement = true;
             nodes.push(self.parse_expressions());
         }
         self.go();
 
+        self.consts = outer_consts;
+        self.in_statement_head = in_statement_head;
+
         ASTNode::CodeBlock(nodes)
     }
 
@@ -694,6 +915,9 @@ impl Parser {
             return Vec::new();
         }
 
+        // the arguments are closed by the paren, so layouts can be created in them inside of a statement head too
+        let in_statement_head = std::mem::replace(&mut self.in_statement_head, false);
+
         let mut list: Vec<ASTNode> = vec![self.parse_expressions()];
         let mut tk = self.go();
 
@@ -703,6 +927,8 @@ impl Parser {
             //dbg!(&tk);
         }
 
+        self.in_statement_head = in_statement_head;
+
         // self.expect_token(Token::Sign(SignType::Paren(Direction::Close)), "Expected a closing paren.");
 
         list
@@ -742,7 +968,9 @@ impl Parser {
     fn parse_if_declaration(&mut self) -> ASTNode {
         self.go(); // if
 
+        let in_statement_head = std::mem::replace(&mut self.in_statement_head, true);
         let condition = Box::new(self.parse_expressions());
+        self.in_statement_head = in_statement_head;
 
         let block = Box::new(self.parse_code_block());
 
@@ -763,6 +991,62 @@ impl Parser {
         })
     }
 
+    fn parse_try_statement(&mut self) -> ASTNode {
+        let token = self.go(); // `try`
+        let in_try = std::mem::replace(&mut self.in_try, true);
+
+        let block = Box::new(self.parse_code_block());
+
+        let catch = if self.curr().value == TokenValue::Keyword(KeywordType::Catch) {
+            self.go(); // `catch`
+
+            let name = if self.curr().value == TokenValue::Sign(SignType::Paren(Direction::Open)) {
+                self.go(); // `(`
+
+                let TokenValue::Identifier(name) = self.go().value else {
+                    err!(ft self.last(), self.root.clone(), "Expected the name of the caught value after `catch (`.");
+                    self.set_end();
+                    return ASTNode::InternalStop(self.last().line, self.last().file_name);
+                };
+
+                self.expect_token(TokenValue::Sign(SignType::Paren(Direction::Close)), "Expected a closing paren.");
+
+                Some(name)
+            } else {
+                None
+            };
+
+            Some(CatchClause {
+                name,
+                block: Box::new(self.parse_code_block()),
+            })
+        } else {
+            None
+        };
+
+        let finally = if self.curr().value == TokenValue::Keyword(KeywordType::Finally) {
+            self.go(); // `finally`
+
+            Some(Box::new(self.parse_code_block()))
+        } else {
+            None
+        };
+
+        self.in_try = in_try;
+
+        if catch.is_none() && finally.is_none() {
+            err!(ft token.clone(), self.root.clone(), "Expected `catch` or `finally` after the block of `try`.");
+            self.set_end();
+            return ASTNode::InternalStop(token.line, token.file_name);
+        }
+
+        ASTNode::TryStatement(TryStatement {
+            block,
+            catch,
+            finally,
+        })
+    }
+
     fn parse_once_declaration(&mut self) -> ASTNode {
         self.go(); // `once`
 
@@ -806,7 +1090,9 @@ impl Parser {
 
     fn parse_use_module(&mut self) -> ASTNode {
         let mut path = String::new();
-        let mut mode: Option<ModulePathMode> = None;
+        // modules are looked up next to the importing one, unless a library or a raw path is given
+        let mut lib = self.lib.clone();
+        let mut dir = self.relative_root.clone();
         let tk = self.go();
 
         if let TokenValue::Sign(i) = tk.value {
@@ -822,7 +1108,8 @@ impl Parser {
                         return ASTNode::InternalStop(tk.line, tk.file_name);
                     };
 
-                    mode = Some(ModulePathMode::Static(Into::<PathBuf>::into(str).canonicalize().unwrap()))
+                    lib = None;
+                    dir = format!("{}/", str.trim_end_matches('/'));
                 }
                 SignType::At => {
                     let str = if let TokenValue::Identifier(s) = self.go().value {
@@ -836,7 +1123,8 @@ impl Parser {
                     };
 
 
-                    mode = Some(ModulePathMode::Lib(str))
+                    lib = Some(str);
+                    dir = String::new();
                 },
                 _ => {
                     err!(
@@ -870,36 +1158,154 @@ impl Parser {
             }
         }
 
-        self.expect_token(
-            TokenValue::Sign(SignType::TildeArrow),
-            "Expected a tilde-arrow (~>) to define the import symbol.",
-        );
+        let import = if self.curr().value == TokenValue::Keyword(KeywordType::As) {
+            self.go(); // `as`
 
-        let module = Module::new(path.clone());
+            if let TokenValue::Identifier(namespace) = self.go().value {
+                ModuleImport::Namespace(namespace)
+            } else {
+                err!(ft self.last(), self.root.clone(), "Expected an identifier to name the namespace.");
+                self.set_end();
+                return ASTNode::InternalStop(self.last().line, self.last().file_name);
+            }
+        } else {
+            self.expect_token(
+                TokenValue::Sign(SignType::TildeArrow),
+                "Expected a tilde-arrow (~>) to define the import symbol.",
+            );
 
-        if let Some(ModulePathMode::Lib(s)) = mode {
-            mode = Some(ModulePathMode::Static(self.libs.get(&s).cloned().unwrap()));
+            match self.parse_import_symbols() {
+                Some(import) => import,
+                None => return ASTNode::InternalStop(self.last().line, self.last().file_name),
+            }
+        };
+
+        let request = ModuleRequest {
+            lib,
+            path: format!("{}{}", dir, path.trim_start_matches(':').replace(':', "/")),
+        };
+
+        let resolved = match self.resolver.resolve(&request) {
+            Ok(v) => v,
+            Err(e) => {
+                err!(ft tk, self.root.clone(), "{}", e);
+                self.set_end();
+                return ASTNode::InternalStop(self.last().line, self.last().file_name);
+            }
+        };
+
+        let id = resolved.id;
+
+        if self.module_storage.is_parsing(&id) {
+            let mut chain = self.module_storage.import_chain(&self.module.id());
+            chain.push(id);
+
+            err!(ft tk, self.root.clone(), "Import cycle detected: {}", chain.join(" -> "));
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
         }
 
-        let src =
-            mosa_fs::read_from_path(path.clone(), self.root.clone(), self.relative_root.clone(), mode.clone());
+        self.module_storage.add_import(self.module.id(), id.clone());
 
-        let mut parser = Parser::new(
-            src,
-            module,
-            self.module_storage.clone(),
-            self.root.clone(),
-            mosa_fs::relative_from(path.clone(), mode),
-            self.libs.clone()
-        );
-        parser.gen_ast();
+        // every module is parsed once, later imports reuse it
+        if self.module_storage.get(&id).is_none() {
+            let relative_root = match request.path.rsplit_once('/') {
+                Some((parent, _)) => format!("{}/", parent),
+                None => String::new(),
+            };
 
-        if let TokenValue::Identifier(symbol) = self.go().value {
-            ASTNode::UseModule(path, symbol)
+            let mut parser = Parser::new(
+                resolved.source,
+                Module::new(request.path.clone(), id.clone()),
+                self.module_storage.clone(),
+                self.root.clone(),
+                relative_root,
+                self.resolver.clone()
+            );
+            parser.lib = request.lib.clone();
+            parser.gen_ast();
+        }
+
+        for (symbol, local) in self.imported_symbols(&id, &import) {
+            if let Some(folded) = self
+                .module_storage
+                .get(&id)
+                .and_then(|m| m.unmodulated_exported_consts().get(&symbol).cloned())
+            {
+                self.consts.insert(local, folded);
+            }
+        }
+
+        if let ModuleImport::Namespace(namespace) = &import {
+            self.namespaces.insert(namespace.clone());
+        }
+
+        ASTNode::UseModule(id, import)
+    }
+
+    /// Parses what follows `~>`, either `symbol [as alias]`, `[symbol [as alias], ...]` or `*`.
+    fn parse_import_symbols(&mut self) -> Option<ModuleImport> {
+        match self.curr().value {
+            TokenValue::Operator(OperatorType::Multiply) => {
+                self.go(); // `*`
+                Some(ModuleImport::All)
+            }
+            TokenValue::Sign(SignType::Brace(Direction::Open)) => {
+                self.go(); // `[`
+                let mut symbols = vec![];
+
+                while self.curr().value != TokenValue::Sign(SignType::Brace(Direction::Close)) {
+                    symbols.push(self.parse_import_symbol()?);
+
+                    if self.curr().value == TokenValue::Sign(SignType::Comma) {
+                        self.go();
+                    }
+                }
+                self.go(); // `]`
+
+                Some(ModuleImport::Symbols(symbols))
+            }
+            _ => Some(ModuleImport::Symbols(vec![self.parse_import_symbol()?])),
+        }
+    }
+
+    fn parse_import_symbol(&mut self) -> Option<(String, String)> {
+        let TokenValue::Identifier(symbol) = self.go().value else {
+            err!(ft self.last(), self.root.clone(), "Expected an identifier to define the imported symbol.");
+            self.set_end();
+            return None;
+        };
+
+        if self.curr().value != TokenValue::Keyword(KeywordType::As) {
+            return Some((symbol.clone(), symbol));
+        }
+
+        self.go(); // `as`
+
+        if let TokenValue::Identifier(alias) = self.go().value {
+            Some((symbol, alias))
         } else {
-            err!(ft self.curr(), self.root.clone(), "Expected an identifier to define the imported symbol.");
+            err!(ft self.last(), self.root.clone(), "Expected an identifier after `as`.");
             self.set_end();
-            ASTNode::InternalStop(self.curr().line, self.curr().file_name)
+            None
+        }
+    }
+
+    /// Pairs of the exported symbols and the names they get in this module.
+    fn imported_symbols(&self, path: &String, import: &ModuleImport) -> Vec<(String, String)> {
+        let exported = self
+            .module_storage
+            .get(path)
+            .map(|m| m.exported_symbols())
+            .unwrap_or_default();
+
+        match import {
+            ModuleImport::Symbols(symbols) => symbols.clone(),
+            ModuleImport::All => exported.into_iter().map(|s| (s.clone(), s)).collect(),
+            ModuleImport::Namespace(namespace) => exported
+                .into_iter()
+                .map(|s| (s.clone(), format!("{}::{}", namespace, s)))
+                .collect(),
         }
     }
 
@@ -948,7 +1354,9 @@ impl Parser {
     fn parse_for_expression(&mut self) -> ASTNode {
         self.go(); // `for`
 
+        let in_statement_head = std::mem::replace(&mut self.in_statement_head, true);
         let iterable = self.parse_expressions();
+        self.in_statement_head = in_statement_head;
         let block = self.parse_code_block();
 
         ASTNode::ForStatement(ForStatement {
@@ -1047,24 +1455,73 @@ impl Parser {
             return ASTNode::InternalStop(self.last().line, self.last().file_name);
         }
 
-        let entries = self.parse_layout_entries();
+        let (entries, spreads) = self.parse_layout_entries();
 
-        let tr = self.try_parse_internal_mix(identifier.clone());
+        let derives = if self.curr().value == TokenValue::Keyword(KeywordType::Derive) {
+            match self.parse_derive() {
+                Some(v) => v,
+                None => return ASTNode::InternalStop(self.last().line, self.last().file_name),
+            }
+        } else {
+            vec![]
+        };
+
+        let declaration = LayoutDeclaration {
+            name: identifier.clone(),
+            fields: entries,
+            spreads,
+            derives,
+        };
+
+        let tr = self.try_parse_internal_mix(identifier);
 
         if let Some(mix) = tr {
-            ASTNode::InternalMulti(vec![
-                ASTNode::LayoutDeclaration(LayoutDeclaration {
-                    name: identifier,
-                    fields: entries,
-                }),
-                mix,
-            ])
+            ASTNode::InternalMulti(vec![ASTNode::LayoutDeclaration(declaration), mix])
         } else {
-            ASTNode::LayoutDeclaration(LayoutDeclaration {
-                name: identifier,
-                fields: entries,
-            })
+            ASTNode::LayoutDeclaration(declaration)
+        }
+    }
+
+    fn parse_derive(&mut self) -> Option<Vec<Derive>> {
+        self.go(); // `derive`
+
+        if self.go().value != TokenValue::Sign(SignType::Brace(Direction::Open)) {
+            err!(ft self.last(), self.root.clone(), "Expected an opening brace (`[`) after `derive`.");
+            self.set_end();
+            return None;
+        }
+
+        let mut derives = vec![];
+
+        while self.curr().value != TokenValue::Sign(SignType::Brace(Direction::Close)) && !self.is_end() {
+            let tk = self.go();
+
+            if let TokenValue::Identifier(id) = &tk.value {
+                if let Ok(derive) = id.parse::<Derive>() {
+                    derives.push(derive);
+                } else {
+                    err!(ft tk, self.root.clone(), "Cannot derive `{}`, expected one of Default, Display, Eq, Clone, Json.", id);
+                    self.set_end();
+                    return None;
+                }
+            } else {
+                err!(ft tk, self.root.clone(), "Expected a name of the derived functionality.");
+                self.set_end();
+                return None;
+            }
+
+            if self.curr().value == TokenValue::Sign(SignType::Comma) {
+                self.go();
+            }
         }
+
+        if self.go().value != TokenValue::Sign(SignType::Brace(Direction::Close)) {
+            err!(ft self.last(), self.root.clone(), "Expected a closing brace (`]`) after the derived functionality.");
+            self.set_end();
+            return None;
+        }
+
+        Some(derives)
     }
 
     fn try_parse_internal_mix(&mut self, internal_ident: String) -> Option<ASTNode> {
@@ -1077,16 +1534,18 @@ impl Parser {
         }
     }
 
-    fn parse_layout_entries(&mut self) -> HashMap<String, FieldParserDescription> {
-        let mut res = HashMap::new();
+    fn parse_layout_entries(&mut self) -> (IndexMap<String, FieldParserDescription>, Vec<String>) {
+        let mut res = IndexMap::new();
+        let mut spreads = vec![];
 
-        if let TokenValue::Identifier(id) = self.curr().value {
-            res.insert(id, self.parse_layout_single_entry().unwrap().1);
-        }
+        self.parse_layout_entry(&mut res, &mut spreads);
 
         let mut tk = self.go();
 
-        if self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Close)) {
+        // a trailing comma, `{ a = 1, }`
+        if tk.value == TokenValue::Sign(SignType::Comma)
+            && self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
+        {
             tk = self.go();
         }
 
@@ -1094,9 +1553,7 @@ impl Parser {
             && self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
             && !self.is_end()
         {
-            if let TokenValue::Identifier(id) = self.curr().value {
-                res.insert(id, self.parse_layout_single_entry().unwrap().1);
-            }
+            self.parse_layout_entry(&mut res, &mut spreads);
             // if self.curr() == Token::Sign(SignType::Comma) {
             //     self.go();
             // }
@@ -1114,7 +1571,28 @@ impl Parser {
             self.set_end();
         }
 
-        res
+        (res, spreads)
+    }
+
+    fn parse_layout_entry(
+        &mut self,
+        res: &mut IndexMap<String, FieldParserDescription>,
+        spreads: &mut Vec<String>,
+    ) {
+        if let TokenValue::Identifier(id) = self.curr().value {
+            if let Some((_, entry)) = self.parse_layout_single_entry() {
+                res.insert(id, entry);
+            }
+        } else if self.curr().value == TokenValue::Sign(SignType::DoubleDot) {
+            self.go(); // `..`
+
+            if let TokenValue::Identifier(id) = self.go().value {
+                spreads.push(id);
+            } else {
+                err!(ft self.last(), self.root.clone(), "Expected a layout name to embed after `..`");
+                self.set_end();
+            }
+        }
     }
 
     fn parse_layout_single_entry(&mut self) -> Option<(String, FieldParserDescription)> {
@@ -1125,16 +1603,14 @@ impl Parser {
                 return None;
             }
 
-            let type_id: String;
-
-            if let TokenValue::Identifier(r#type) = self.go().value {
-                type_id = r#type;
-            } else {
-                err!(ft self.last(), self.root.clone(), "Expected a type identifier after `{}:`", id);
+            if !matches!(self.curr().value, TokenValue::Identifier(_)) {
+                err!(ft self.curr(), self.root.clone(), "Expected a type identifier after `{}:`", id);
                 self.set_end();
                 return None;
             }
 
+            let type_id = self.parse_data_type().to_string();
+
             let maybe_default_type =
                 (self.curr().value == TokenValue::Operator(OperatorType::Equal)).then(|| {
                     self.go(); // '='
@@ -1174,7 +1650,10 @@ impl Parser {
 
         let mut tk = self.go();
 
-        if self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Close)) {
+        // a trailing comma, `{ a = 1, }`
+        if tk.value == TokenValue::Sign(SignType::Comma)
+            && self.curr().value == TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
+        {
             tk = self.go();
         }
 
@@ -1239,21 +1718,20 @@ impl Parser {
     fn parse_layout_property_access(&mut self) -> ASTNode {
         let mut expr = self.parse_call();
 
-        if let TokenValue::Identifier(ident) = self.peek().value {
-            if self.curr().value == TokenValue::Sign(SignType::Dot) {
+        while self.curr().value == TokenValue::Sign(SignType::Dot) {
+            if let TokenValue::Identifier(ident) = self.peek().value {
                 self.go(); self.go(); // one for the ident, one for the dot
                 expr = ASTNode::LayoutFieldAccess(Box::new(expr), ident);
-                
+
                 if self.curr().value == TokenValue::Sign(SignType::Paren(Direction::Open)) {
                     let args = self.parse_fn_call_arg_list();
                     expr = ASTNode::FunctionCall(Box::new(expr), args)
                 }
-            }
-        } else {
-            if self.curr().value == TokenValue::Sign(SignType::Dot) {
+            } else {
                 err!(ft self.last(), self.root.clone(), "Expected a field name to access from a layout.");
                 self.set_end();
-                expr = ASTNode::InternalStop(self.last().line, self.last().file_name)
+                expr = ASTNode::InternalStop(self.last().line, self.last().file_name);
+                break;
             }
         }
 
@@ -1280,11 +1758,7 @@ impl Parser {
 
                     self.expect_token(TokenValue::Sign(SignType::Brace(Direction::Close)), "Expected a closing brace to finish generic definition.");
 
-                    dbg!(&generics, &ident);
-                    let ty = DataType::from_str(ident, generics);
-                    dbg!(&ty);
-
-                    ty
+                    DataType::from_str(ident, generics)
                 } else {
                     DataType::from_str(ident, vec![])
                 }
@@ -1321,7 +1795,9 @@ impl Parser {
 
         let mut functions: Vec<ParserFunctionData> = Vec::new();
 
-        while self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close)) {
+        while self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
+            && !self.is_end()
+        {
             functions.push(self.parse_mix_function(Some(&identifier)));
         }
 
@@ -1334,6 +1810,55 @@ impl Parser {
         ASTNode::MixStatement(identifier, functions)
     }
 
+    fn parse_expand(&mut self) -> ASTNode {
+        self.go(); // `expand`
+
+        let identifier = if let TokenValue::Identifier(id) = self.go().value {
+            id
+        } else {
+            err!(ft self.last(), self.root.clone(), "Expected an identifier marking the layout name.");
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
+        };
+
+        if self.go().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Open)) {
+            err!(ft self.last(), self.root.clone(), "Expected an opening curly braces.");
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
+        }
+
+        let mut via = vec![];
+
+        while self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
+            && !self.is_end()
+        {
+            if self.curr().value == TokenValue::Sign(SignType::Semicolon) {
+                self.go();
+                continue;
+            }
+
+            if self.go().value != TokenValue::Sign(SignType::DollarSign)
+                || self.go().value != TokenValue::Identifier("via".to_string())
+            {
+                err!(ft self.last(), self.root.clone(), "Expected `$via <field>` in the expand statement.");
+                self.set_end();
+                return ASTNode::InternalStop(self.last().line, self.last().file_name);
+            }
+
+            if let TokenValue::Identifier(field) = self.go().value {
+                via.push(field);
+            } else {
+                err!(ft self.last(), self.root.clone(), "Expected a field name after `$via`.");
+                self.set_end();
+                return ASTNode::InternalStop(self.last().line, self.last().file_name);
+            }
+        }
+
+        self.go(); // `}`
+
+        ASTNode::ExpandStatement(identifier, via)
+    }
+
     fn parse_mix_function(&mut self, identifier: Option<&String>) -> ParserFunctionData {
         //dbg!(&&&&&&self.curr());
 
@@ -1382,10 +1907,173 @@ impl Parser {
         }
     }
 
+    fn parse_class_declaration(&mut self) -> ASTNode {
+        self.go(); // `class`
+
+        let identifier = if let TokenValue::Identifier(ident) = self.go().value {
+            ident
+        } else {
+            err!(ft self.last(), self.root.clone(), "Expected an identifier after `class`");
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
+        };
+
+        let mut parent = None;
+
+        if self.curr().value == TokenValue::Operator(OperatorType::Smaller) {
+            self.go(); // `<`
+
+            if let TokenValue::Identifier(ident) = self.go().value {
+                parent = Some(ident);
+            } else {
+                err!(ft self.last(), self.root.clone(), "Expected a class name to extend after `<`");
+                self.set_end();
+                return ASTNode::InternalStop(self.last().line, self.last().file_name);
+            }
+        }
+
+        let constructor = (self.curr().value == TokenValue::Sign(SignType::Paren(Direction::Open)))
+            .then(|| self.parse_class_special_block(&identifier, "constructor"));
+
+        if self.go().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Open)) {
+            err!(ft self.last(), self.root.clone(), "Expected an opening curly brace (`{{`) for the class members.");
+            self.set_end();
+            return ASTNode::InternalStop(self.last().line, self.last().file_name);
+        }
+
+        let mut fields = IndexMap::new();
+        let mut functions = vec![];
+        let mut private = HashSet::new();
+
+        while self.curr().value != TokenValue::Sign(SignType::CurlyBrace(Direction::Close))
+            && !self.is_end()
+        {
+            if self.curr().value == TokenValue::Sign(SignType::Semicolon) {
+                self.go();
+                continue;
+            }
+
+            let is_private = self.curr().value == TokenValue::Keyword(KeywordType::Pri);
+
+            if is_private {
+                self.go(); // `pri`
+            }
+
+            let name = match self.curr().value {
+                TokenValue::Keyword(KeywordType::Let) => {
+                    self.go(); // `let`
+
+                    match self.parse_class_field() {
+                        Some((name, field)) => {
+                            fields.insert(name.clone(), field);
+                            name
+                        }
+                        None => return ASTNode::InternalStop(self.last().line, self.last().file_name),
+                    }
+                }
+                TokenValue::Keyword(KeywordType::Fn)
+                | TokenValue::Keyword(KeywordType::Gen)
+                | TokenValue::Keyword(KeywordType::Tied) => {
+                    let function = self.parse_mix_function(Some(&identifier));
+                    let name = function.name.clone();
+                    functions.push(function);
+                    name
+                }
+                _ => {
+                    err!(ft self.curr(), self.root.clone(), "Expected a field (`let`) or a function (`fn`) in the class members.");
+                    self.set_end();
+                    return ASTNode::InternalStop(self.curr().line, self.curr().file_name);
+                }
+            };
+
+            if is_private {
+                private.insert(name);
+            }
+        }
+
+        self.go(); // `}`
+
+        // `() { ... }` right after the members is the destructor
+        let destructor = (self.curr().value == TokenValue::Sign(SignType::Paren(Direction::Open))
+            && self.peek().value == TokenValue::Sign(SignType::Paren(Direction::Close))
+            && self.tokens.get(2).map(|t| t.value.clone())
+                == Some(TokenValue::Sign(SignType::CurlyBrace(Direction::Open))))
+        .then(|| self.parse_class_special_block(&identifier, "destructor"));
+
+        ASTNode::ClassDeclaration(Box::new(ClassDeclaration {
+            name: identifier,
+            parent,
+            constructor,
+            fields,
+            functions,
+            destructor,
+            private,
+        }))
+    }
+
+    /// Parses `(args) { body }` as a tied function of the class.
+    fn parse_class_special_block(&mut self, class: &str, name: &str) -> ParserFunctionData {
+        let mut args = IndexMap::new();
+        args.insert(
+            "self".to_string(),
+            DataType::Complex(ComplexDataType::LayoutOrEnum(class.to_string())),
+        );
+        args.extend(self.parse_fn_args_list());
+
+        let body = if let ASTNode::CodeBlock(code) = self.parse_code_block() {
+            code
+        } else {
+            unreachable!()
+        };
+
+        ParserFunctionData {
+            name: name.to_string(),
+            args,
+            body,
+            return_type: DataType::Primitive(PrimitiveDataType::Null),
+            tied: true,
+        }
+    }
+
+    fn parse_class_field(&mut self) -> Option<(String, FieldParserDescription)> {
+        let id = if let TokenValue::Identifier(id) = self.go().value {
+            id
+        } else {
+            err!(ft self.last(), self.root.clone(), "Expected a field name after `let`");
+            self.set_end();
+            return None;
+        };
+
+        if self.go().value != TokenValue::Sign(SignType::Colon) {
+            err!(ft self.last(), self.root.clone(), "Class fields require a type, expected a colon after `{}`", id);
+            self.set_end();
+            return None;
+        }
+
+        let type_id = self.parse_data_type().to_string();
+
+        let default_value =
+            (self.curr().value == TokenValue::Operator(OperatorType::Equal)).then(|| {
+                self.go(); // '='
+
+                Box::new(self.parse_expressions())
+            });
+
+        Some((
+            id,
+            FieldParserDescription {
+                type_id,
+                default_value,
+            },
+        ))
+    }
+
     fn parse_exp(&mut self) -> ASTNode {
         self.go();
 
-        if self.curr().value == TokenValue::Keyword(KeywordType::Fn) {
+        if self.curr().value == TokenValue::Keyword(KeywordType::Fn)
+            || self.curr().value == TokenValue::Keyword(KeywordType::Gen)
+        {
             let fun = self.parse_fn_declaration();
 
             let fd =
@@ -1434,10 +2122,71 @@ impl Parser {
             } else {
                 unreachable!()
             }
+        } else if self.curr().value == TokenValue::Keyword(KeywordType::Class) {
+            let class = self.parse_class_declaration();
+
+            if let ASTNode::ClassDeclaration(cd) = &class {
+                self.module.push_unmodulated_layout(
+                    cd.name.clone(),
+                    LayoutDeclaration {
+                        name: cd.name.clone(),
+                        fields: cd.fields.clone(),
+                        spreads: vec![],
+                        derives: vec![],
+                    },
+                );
+            }
+
+            class
+        } else if self.curr().value == TokenValue::Keyword(KeywordType::Const) {
+            let constant = self.parse_const_declaration();
+
+            if let ASTNode::VariableDeclaration(_, name, _, value) = &constant {
+                self.module.push_unmodulated_const(name.clone(), *value.clone());
+                self.module.push_unmodulated_symbol(name.clone());
+            }
+
+            constant
+        } else if self.curr().value == TokenValue::Keyword(KeywordType::Immut) {
+            let variable = self.parse_variable_declaration();
+
+            if let ASTNode::VariableDeclaration(_, name, _, _) = &variable {
+                self.module.push_unmodulated_symbol(name.clone());
+            }
+
+            variable
         } else if self.curr().value == TokenValue::Keyword(KeywordType::Enum) {
-            unreachable!()
+            let enumeration = self.parse_enum_declaration();
+
+            if let ASTNode::EnumDeclaration(name, _) = &enumeration {
+                self.module.push_unmodulated_symbol(name.clone());
+            }
+
+            enumeration
+        } else if self.curr().value == TokenValue::Keyword(KeywordType::Use) {
+            let import = self.parse_use();
+
+            match &import {
+                ASTNode::UseNative(native) => self.module.push_unmodulated_symbol(native.name.clone()),
+                ASTNode::UseModule(path, module_import) => {
+                    for (_, local) in self.imported_symbols(path, module_import) {
+                        if let Some(folded) = self.get_const(&local) {
+                            self.module.push_unmodulated_const(local.clone(), folded);
+                        }
+
+                        self.module.push_unmodulated_symbol(local)
+                    }
+                }
+                _ => {}
+            }
+
+            import
+        } else if self.curr().value == TokenValue::Keyword(KeywordType::Let) {
+            err!(ft self.curr(), self.root.clone(), "Only immutable variables can be exported, use `exp immut let`.");
+            self.set_end();
+            ASTNode::InternalStop(self.curr().line, self.curr().file_name)
         } else {
-            err!(ft self.curr(), self.root.clone(), "Now, exports are only supported for functions.");
+            err!(ft self.curr(), self.root.clone(), "Only functions, layouts, classes, enums, immutable variables, constants and imports can be exported.");
             self.set_end();
             ASTNode::InternalStop(self.curr().line, self.curr().file_name)
         }
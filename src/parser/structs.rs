@@ -2,7 +2,7 @@ use crate::global::DataType;
 use crate::interpreter::scope::{FnArgs, FunctionData, RuntimeScopeW};
 use indexmap::IndexMap;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum ASTNode {
@@ -31,10 +31,23 @@ pub enum ASTNode {
     LayoutCreation(LayoutCreation),
     LayoutFieldAccess(Box<ASTNode>, String),
     MixStatement(String, Vec<ParserFunctionData>),
+    ExpandStatement(String, Vec<String>),
     InternalMulti(Vec<ASTNode>),
-    UseModule(String, String),
+    UseModule(String, ModuleImport),
     Lambda(IndexMap<String, DataType>, Box<ASTNode>, DataType),
     Indexing(Box<ASTNode>, Box<ASTNode>),
+    Range(RangeExpression),
+    ClassDeclaration(Box<ClassDeclaration>),
+    ClassConstruction(String),
+    /// body of a function generated by `derive`, holds the layout and the function name
+    DerivedFunction(String, String),
+    /// body of a `gen fn`, holds the statements and the type of the yielded values
+    Generator(Vec<ASTNode>, DataType),
+    Yield(Box<ASTNode>),
+    Throw(Box<ASTNode>),
+    TryStatement(TryStatement),
+    /// `Ok(value)` if the bool is set, `Err(value)` otherwise
+    ResultCreation(bool, Box<ASTNode>),
     InternalStop(usize, String),
 }
 
@@ -44,10 +57,14 @@ pub enum AssignmentProperty {
     LayoutField(Box<ASTNode>, String),
 }
 
-#[derive(Clone)]
-pub enum ModulePathMode {
-    Static(PathBuf),
-    Lib(String)
+#[derive(Clone, PartialEq, Debug)]
+pub enum ModuleImport {
+    /// `~>a`, `~>a as b` and `~>[a, b as c]`, pairs of the exported symbol and its local name
+    Symbols(Vec<(String, String)>),
+    /// `~>*`
+    All,
+    /// `use path as ns;`, the symbols are accessed with `ns::symbol`
+    Namespace(String),
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -56,12 +73,36 @@ pub struct OnceStatement {
     pub else_block: Option<Box<ASTNode>>,
 }
 
+/// `try { } catch (e) { } finally { }`, one of `catch` and `finally` can be left out
+#[derive(Clone, PartialEq, Debug)]
+pub struct TryStatement {
+    pub block: Box<ASTNode>,
+    pub catch: Option<CatchClause>,
+    pub finally: Option<Box<ASTNode>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CatchClause {
+    /// the variable the thrown value is assigned to, `catch { }` ignores it
+    pub name: Option<String>,
+    pub block: Box<ASTNode>,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct ForStatement {
     pub iterable: Box<ASTNode>,
     pub block: Box<ASTNode>,
 }
 
+/// `start..end`, `start..=end`, optionally followed by `step n`
+#[derive(Clone, PartialEq, Debug)]
+pub struct RangeExpression {
+    pub start: Box<ASTNode>,
+    pub end: Box<ASTNode>,
+    pub step: Option<Box<ASTNode>>,
+    pub inclusive: bool,
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct IfStatement {
     pub condition: Box<ASTNode>,
@@ -96,7 +137,34 @@ pub struct BinaryExpression {
 #[derive(Clone, PartialEq, Debug)]
 pub struct LayoutDeclaration {
     pub name: String,
-    pub fields: HashMap<String, FieldParserDescription>,
+    pub fields: IndexMap<String, FieldParserDescription>,
+    /// layouts embedded with `..Name`, their fields are copied into this one
+    pub spreads: Vec<String>,
+    pub derives: Vec<Derive>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Derive {
+    Default,
+    Display,
+    Eq,
+    Clone,
+    Json,
+}
+
+impl FromStr for Derive {
+    type Err = ();
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "Default" => Ok(Derive::Default),
+            "Display" => Ok(Derive::Display),
+            "Eq" => Ok(Derive::Eq),
+            "Clone" => Ok(Derive::Clone),
+            "Json" => Ok(Derive::Json),
+            _ => Err(()),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -105,6 +173,18 @@ pub struct FieldParserDescription {
     pub default_value: Option<Box<ASTNode>>,
 }
 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClassDeclaration {
+    pub name: String,
+    pub parent: Option<String>,
+    pub constructor: Option<ParserFunctionData>,
+    pub fields: IndexMap<String, FieldParserDescription>,
+    pub functions: Vec<ParserFunctionData>,
+    pub destructor: Option<ParserFunctionData>,
+    /// names of the `pri` fields and functions
+    pub private: HashSet<String>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ParserFunctionData {
     pub name: String,
@@ -136,7 +216,6 @@ pub enum Operand {
     BiggerEqual,
     SmallerEqual,
     Equal,
-    DoubleDot,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -2,4 +2,8 @@ pub use crate::wrapper::MoSaRunner;
 pub use crate::wrapper::MoSaBinding;
 pub use crate::interpreter::structs::RuntimeValue;
 pub use crate::interpreter::scope::RuntimeScope;
-pub use crate::wrapper::bindings::MoSaNativeGen;
\ No newline at end of file
+pub use crate::wrapper::bindings::MoSaNativeGen;
+pub use crate::modules::resolver::{
+    FileSystemResolver, MemoryResolver, ModuleResolver, StaticResolver,
+};
+pub use crate::stdlib::time::{Clock, FakeClock, SystemClock};
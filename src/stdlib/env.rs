@@ -0,0 +1,83 @@
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::io::{from_io_error, io_error, not_granted};
+use crate::stdlib::{str_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::env;
+
+fn binding(
+    name: &'static str,
+    enabled: bool,
+    f: impl Fn(&[RuntimeValue]) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>env~>{}", name), move |args| {
+        if !enabled {
+            return not_granted("std:env", "grant_env");
+        }
+
+        f(&args)
+    })
+}
+
+/// Names that `std::env::set_var` would panic on.
+fn check_name(name: &str) -> Option<RuntimeValue> {
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Some(io_error(
+            "invalidInput",
+            format!("`{}` is not a valid environment variable name.", name),
+        ));
+    }
+
+    None
+}
+
+/// `args` are the script arguments the host passed with `MoSaRunner::with_args`.
+pub fn bindings(enabled: bool, args: Vec<String>) -> Vec<MoSaBinding> {
+    vec![
+        binding("get", enabled, |args| {
+            match env::var(str_arg(args, 0, "get")) {
+                Ok(v) => RuntimeValue::String(v),
+                Err(_) => RuntimeValue::Null,
+            }
+        }),
+        binding("set", enabled, |args| {
+            let name = str_arg(args, 0, "set");
+            let value = str_arg(args, 1, "set");
+
+            if let Some(e) = check_name(name) {
+                return e;
+            }
+
+            if value.contains('\0') {
+                return io_error("invalidInput", format!("The value of `{}` cannot contain a null character.", name));
+            }
+
+            env::set_var(name, value);
+
+            RuntimeValue::Null
+        }),
+        binding("remove", enabled, |args| {
+            let name = str_arg(args, 0, "remove");
+
+            if let Some(e) = check_name(name) {
+                return e;
+            }
+
+            env::remove_var(name);
+
+            RuntimeValue::Null
+        }),
+        binding("cwd", enabled, |_| match env::current_dir() {
+            Ok(v) => RuntimeValue::String(v.to_string_lossy().to_string()),
+            Err(e) => from_io_error(&e, "Cannot read the current directory"),
+        }),
+        binding("setCwd", enabled, |args| {
+            match env::set_current_dir(str_arg(args, 0, "setCwd")) {
+                Ok(_) => RuntimeValue::Null,
+                Err(e) => from_io_error(&e, &format!("Cannot change the current directory to `{}`", args[0])),
+            }
+        }),
+        binding("args", enabled, move |_| {
+            to_iterable(args.iter().map(|v| RuntimeValue::String(v.clone())))
+        }),
+    ]
+}
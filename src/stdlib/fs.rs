@@ -0,0 +1,168 @@
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::io::{from_io_error, io_error};
+use crate::stdlib::{str_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// The directories scripts may access, every path is checked after resolving `..` and symlinks.
+#[derive(Debug)]
+pub struct Sandbox {
+    roots: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots: roots
+                .into_iter()
+                .map(|root| root.canonicalize().unwrap_or_else(|_| normalize(&root)))
+                .collect(),
+        }
+    }
+
+    /// The real path of `path`, or the message of the `outsideSandbox` error if it is outside of every root.
+    pub fn confine(&self, path: &str) -> Result<PathBuf, String> {
+        let Some(first) = self.roots.first() else {
+            return Err(format!("Cannot access `{}`, the host didn't grant any directories.", path));
+        };
+
+        let real = resolve_existing(&normalize(&first.join(path)));
+
+        if self.roots.iter().any(|root| real.starts_with(root)) {
+            Ok(real)
+        } else {
+            Err(format!("Cannot access `{}`, it is outside of the granted directories.", path))
+        }
+    }
+}
+
+/// Removes `.` and `..` without touching the disk, as the path doesn't have to exist.
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                res.pop();
+            }
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// Canonicalizes the longest existing part of the path, so symlinks can't lead out of the sandbox.
+fn resolve_existing(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut rest = vec![];
+
+    while !existing.exists() {
+        match (existing.file_name().map(|n| n.to_os_string()), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name);
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+
+    let mut res = existing.canonicalize().unwrap_or(existing);
+
+    res.extend(rest.into_iter().rev());
+
+    res
+}
+
+fn binding(
+    name: &'static str,
+    sandbox: &Arc<Sandbox>,
+    f: impl Fn(&[RuntimeValue], PathBuf) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    let sandbox = sandbox.clone();
+
+    MoSaBinding::new(format!("std~>fs~>{}", name), move |args| {
+        match sandbox.confine(str_arg(&args, 0, name)) {
+            Ok(path) => f(&args, path),
+            Err(message) => io_error("outsideSandbox", message),
+        }
+    })
+}
+
+fn done(res: std::io::Result<()>, context: &str) -> RuntimeValue {
+    match res {
+        Ok(_) => RuntimeValue::Null,
+        Err(e) => from_io_error(&e, context),
+    }
+}
+
+pub fn bindings(roots: Vec<PathBuf>) -> Vec<MoSaBinding> {
+    let sandbox = Arc::new(Sandbox::new(roots));
+
+    vec![
+        binding("readText", &sandbox, |args, path| {
+            match fs::read_to_string(&path) {
+                Ok(v) => RuntimeValue::String(v),
+                Err(e) => from_io_error(&e, &format!("Cannot read `{}`", args[0])),
+            }
+        }),
+        binding("writeText", &sandbox, |args, path| {
+            let text = str_arg(args, 1, "writeText");
+
+            done(fs::write(&path, text), &format!("Cannot write `{}`", args[0]))
+        }),
+        binding("appendText", &sandbox, |args, path| {
+            let text = str_arg(args, 1, "appendText");
+            let res = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(text.as_bytes()));
+
+            done(res, &format!("Cannot append to `{}`", args[0]))
+        }),
+        binding("listDir", &sandbox, |args, path| {
+            let entries = fs::read_dir(&path).and_then(|entries| {
+                let mut names = entries
+                    .map(|entry| entry.map(|e| e.file_name().to_string_lossy().to_string()))
+                    .collect::<std::io::Result<Vec<_>>>()?;
+
+                names.sort();
+
+                Ok(names)
+            });
+
+            match entries {
+                Ok(names) => to_iterable(names.into_iter().map(RuntimeValue::String)),
+                Err(e) => from_io_error(&e, &format!("Cannot list `{}`", args[0])),
+            }
+        }),
+        binding("exists", &sandbox, |_, path| RuntimeValue::Bool(path.exists())),
+        binding("isFile", &sandbox, |_, path| RuntimeValue::Bool(path.is_file())),
+        binding("isDir", &sandbox, |_, path| RuntimeValue::Bool(path.is_dir())),
+        binding("createDir", &sandbox, |args, path| {
+            done(fs::create_dir_all(&path), &format!("Cannot create `{}`", args[0]))
+        }),
+        binding("removeDir", &sandbox, {
+            let sandbox = sandbox.clone();
+
+            move |args, path| {
+                if sandbox.roots.contains(&path) {
+                    return io_error(
+                        "permissionDenied",
+                        format!("Cannot remove `{}`, it is a granted directory.", args[0]),
+                    );
+                }
+
+                done(fs::remove_dir_all(&path), &format!("Cannot remove `{}`", args[0]))
+            }
+        }),
+        binding("removeFile", &sandbox, |args, path| {
+            done(fs::remove_file(&path), &format!("Cannot remove `{}`", args[0]))
+        }),
+    ]
+}
@@ -0,0 +1,92 @@
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::{arg, layout};
+use crate::wrapper::MoSaBinding;
+use std::io::{ErrorKind, Read, Write};
+
+/// An `IoError { kind, message }` of `std:io`, the kind is a camelCase `ErrorKind` like `notFound`.
+pub(crate) fn io_error(kind: &str, message: impl Into<String>) -> RuntimeValue {
+    layout(
+        "IoError",
+        vec![
+            ("kind", RuntimeValue::String(kind.to_string())),
+            ("message", RuntimeValue::String(message.into())),
+        ],
+    )
+}
+
+pub(crate) fn from_io_error(e: &std::io::Error, context: &str) -> RuntimeValue {
+    let kind = match e.kind() {
+        ErrorKind::NotFound => "notFound",
+        ErrorKind::PermissionDenied => "permissionDenied",
+        ErrorKind::AlreadyExists => "alreadyExists",
+        ErrorKind::InvalidData => "invalidData",
+        ErrorKind::InvalidInput => "invalidInput",
+        ErrorKind::NotADirectory => "notADirectory",
+        ErrorKind::IsADirectory => "isADirectory",
+        ErrorKind::DirectoryNotEmpty => "directoryNotEmpty",
+        ErrorKind::UnexpectedEof => "unexpectedEof",
+        _ => "other",
+    };
+
+    io_error(kind, format!("{}: {}", context, e))
+}
+
+/// Returned by the functions of a module the host didn't enable, e.g. `std:process`.
+pub(crate) fn not_granted(module: &str, grant: &str) -> RuntimeValue {
+    io_error(
+        "permissionDenied",
+        format!("`{}` is disabled, the host has to enable it with `MoSaRunner::{}`.", module, grant),
+    )
+}
+
+fn write_to(name: &'static str, stderr: bool, new_line: bool) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>io~>{}", name), move |args| {
+        let text = match args.first() {
+            Some(_) => arg(&args, 0, name).to_string(),
+            None => String::new(),
+        };
+        let end = if new_line { "\n" } else { "" };
+
+        let res = if stderr {
+            write!(std::io::stderr(), "{}{}", text, end).and_then(|_| std::io::stderr().flush())
+        } else {
+            write!(std::io::stdout(), "{}{}", text, end).and_then(|_| std::io::stdout().flush())
+        };
+
+        match res {
+            Ok(_) => RuntimeValue::Null,
+            Err(e) => from_io_error(&e, name),
+        }
+    })
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        write_to("print", false, false),
+        write_to("printLn", false, true),
+        write_to("eprint", true, false),
+        write_to("eprintLn", true, true),
+        MoSaBinding::new("std~>io~>readLine", |_| {
+            let mut line = String::new();
+
+            match std::io::stdin().read_line(&mut line) {
+                Ok(0) => RuntimeValue::Null,
+                Ok(_) => RuntimeValue::String(
+                    line.strip_suffix('\n')
+                        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+                        .unwrap_or(&line)
+                        .to_string(),
+                ),
+                Err(e) => from_io_error(&e, "Cannot read a line from stdin"),
+            }
+        }),
+        MoSaBinding::new("std~>io~>readAll", |_| {
+            let mut text = String::new();
+
+            match std::io::stdin().read_to_string(&mut text) {
+                Ok(_) => RuntimeValue::String(text),
+                Err(e) => from_io_error(&e, "Cannot read stdin"),
+            }
+        }),
+    ]
+}
@@ -0,0 +1,227 @@
+use crate::err;
+use crate::global::{DataType, NumType, PrimitiveDataType};
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::{arg, call, function_arg, index_arg, iterable_arg, layout, list_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::cmp::Ordering;
+use std::process::exit;
+
+fn binding(
+    name: &'static str,
+    f: impl Fn(Vec<RuntimeValue>) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>iter~>{}", name), f)
+}
+
+fn bool_type() -> Option<DataType> {
+    Some(DataType::Primitive(PrimitiveDataType::Bool))
+}
+
+fn num_type() -> Option<DataType> {
+    Some(DataType::Primitive(PrimitiveDataType::Num(NumType::Dynamic)))
+}
+
+fn is_true(v: RuntimeValue) -> bool {
+    matches!(v, RuntimeValue::Bool(true))
+}
+
+fn test_all(name: &'static str, any: bool) -> MoSaBinding {
+    binding(name, move |args| {
+        let fd = function_arg(&args, 1, 1, bool_type(), name);
+
+        for (index, item) in iterable_arg(&args, 0, name).enumerate() {
+            if is_true(call(&fd, vec![item], Some(index), name)) == any {
+                return RuntimeValue::Bool(any);
+            }
+        }
+
+        RuntimeValue::Bool(!any)
+    })
+}
+
+/// Numbers and strings have a natural order, anything else needs a comparator.
+fn natural_order(a: &RuntimeValue, b: &RuntimeValue) -> Option<Ordering> {
+    match (a, b) {
+        (RuntimeValue::Number(a), RuntimeValue::Number(b)) => Some(a.total_cmp(b)),
+        (RuntimeValue::String(a), RuntimeValue::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+/// A stable merge sort that stays in bounds whatever `compare` returns, as comparators written in
+/// scripts don't have to be consistent and `sort_by` may panic on them.
+fn merge_sort(
+    items: Vec<RuntimeValue>,
+    compare: &mut impl FnMut(&RuntimeValue, &RuntimeValue) -> Ordering,
+) -> Vec<RuntimeValue> {
+    if items.len() < 2 {
+        return items;
+    }
+
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let mut left = merge_sort(left, compare).into_iter().peekable();
+    let mut right = merge_sort(right, compare).into_iter().peekable();
+    let mut res = Vec::with_capacity(left.len() + right.len());
+
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        let next = if compare(r, l) == Ordering::Less { right.next() } else { left.next() };
+
+        res.extend(next);
+    }
+
+    res.extend(left);
+    res.extend(right);
+    res
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        binding("map", |args| {
+            let fd = function_arg(&args, 1, 1, None, "map");
+
+            to_iterable(
+                list_arg(&args, 0, "map")
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, item)| call(&fd, vec![item], Some(index), "map"))
+                    .collect::<Vec<_>>(),
+            )
+        }),
+        binding("filter", |args| {
+            let fd = function_arg(&args, 1, 1, bool_type(), "filter");
+
+            to_iterable(
+                list_arg(&args, 0, "filter")
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, item)| is_true(call(&fd, vec![item.clone()], Some(*index), "filter")))
+                    .map(|(_, item)| item)
+                    .collect::<Vec<_>>(),
+            )
+        }),
+        binding("fold", |args| {
+            let mut acc = arg(&args, 1, "fold").clone();
+            let fd = function_arg(&args, 2, 2, None, "fold");
+
+            for (index, item) in iterable_arg(&args, 0, "fold").enumerate() {
+                acc = call(&fd, vec![acc, item], Some(index), "fold");
+            }
+
+            acc
+        }),
+        binding("reduce", |args| {
+            let fd = function_arg(&args, 1, 2, None, "reduce");
+            let mut items = iterable_arg(&args, 0, "reduce").enumerate();
+
+            let Some((_, mut acc)) = items.next() else {
+                return RuntimeValue::Null;
+            };
+
+            for (index, item) in items {
+                acc = call(&fd, vec![acc, item], Some(index), "reduce");
+            }
+
+            acc
+        }),
+        test_all("any", true),
+        test_all("all", false),
+        binding("zip", |args| {
+            let second = iterable_arg(&args, 1, "zip");
+
+            to_iterable(iterable_arg(&args, 0, "zip").zip(second).map(|(first, second)| {
+                layout("Pair", vec![("first", first), ("second", second)])
+            }))
+        }),
+        binding("enumerate", |args| {
+            to_iterable(
+                list_arg(&args, 0, "enumerate")
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        layout(
+                            "Entry",
+                            vec![
+                                ("index", RuntimeValue::Number(index as f64)),
+                                ("value", value),
+                            ],
+                        )
+                    }),
+            )
+        }),
+        binding("take", |args| {
+            let count = index_arg(&args, 1, "take");
+
+            to_iterable(iterable_arg(&args, 0, "take").take(count))
+        }),
+        binding("skip", |args| {
+            let count = index_arg(&args, 1, "skip");
+
+            // ranges stay lazy
+            if let RuntimeValue::Range(range) = arg(&args, 0, "skip") {
+                return RuntimeValue::Range(range.skip(count));
+            }
+
+            to_iterable(list_arg(&args, 0, "skip").into_iter().skip(count))
+        }),
+        binding("sum", |args| {
+            let mut sum = 0.0;
+
+            for (index, item) in iterable_arg(&args, 0, "sum").enumerate() {
+                match item {
+                    RuntimeValue::Number(v) => sum += v,
+                    v => {
+                        err!(intrp "`sum` can only add numbers, item {} is `{}`.", index, v);
+                        exit(100)
+                    }
+                }
+            }
+
+            RuntimeValue::Number(sum)
+        }),
+        binding("sort", |args| {
+            let mut items = list_arg(&args, 0, "sort");
+
+            if args.get(1).is_some() {
+                let fd = function_arg(&args, 1, 2, num_type(), "sort");
+
+                // a negative result puts `a` first, like the comparators of most languages
+                items = merge_sort(items, &mut |a, b| {
+                    match call(&fd, vec![a.clone(), b.clone()], None, "sort") {
+                        RuntimeValue::Number(v) if v < 0.0 => Ordering::Less,
+                        RuntimeValue::Number(v) if v > 0.0 => Ordering::Greater,
+                        _ => Ordering::Equal,
+                    }
+                });
+            } else {
+                for pair in items.windows(2) {
+                    if natural_order(&pair[0], &pair[1]).is_none() {
+                        err!(intrp "`sort` can only compare numbers with numbers and strings with strings without a comparator, got `{}` and `{}`.", pair[0], pair[1]);
+                        exit(100)
+                    }
+                }
+
+                items.sort_by(|a, b| natural_order(a, b).unwrap_or(Ordering::Equal));
+            }
+
+            to_iterable(items)
+        }),
+        binding("reverse", |args| {
+            if let RuntimeValue::Range(range) = arg(&args, 0, "reverse") {
+                if !range.end.is_finite() {
+                    err!(intrp "`reverse` cannot reverse the infinite range `{}`.", args[0]);
+                    exit(100)
+                }
+
+                return RuntimeValue::Range(range.reversed());
+            }
+
+            let mut items = list_arg(&args, 0, "reverse");
+
+            items.reverse();
+
+            to_iterable(items)
+        }),
+        binding("collect", |args| to_iterable(list_arg(&args, 0, "collect"))),
+    ]
+}
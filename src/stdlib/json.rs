@@ -0,0 +1,103 @@
+use crate::err;
+use crate::interpreter::derive::value_to_json;
+use crate::interpreter::structs::{ComplexRuntimeValue, LayoutData, RuntimeValue};
+use crate::json;
+use crate::json::JsonValue;
+use crate::stdlib::{arg, layout, str_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::process::exit;
+
+/// Objects become `JsonObject` layouts with a field for every key, so `obj.key` works.
+pub(crate) fn json_to_value(value: &JsonValue) -> RuntimeValue {
+    match value {
+        JsonValue::Null => RuntimeValue::Null,
+        JsonValue::Bool(v) => RuntimeValue::Bool(*v),
+        JsonValue::Number(v) => RuntimeValue::Number(*v),
+        JsonValue::String(v) => RuntimeValue::String(v.clone()),
+        JsonValue::Array(v) => to_iterable(v.iter().map(json_to_value)),
+        JsonValue::Object(v) => layout(
+            "JsonObject",
+            v.iter()
+                .map(|(key, item)| (key.as_str(), json_to_value(item)))
+                .collect(),
+        ),
+    }
+}
+
+fn json_error(message: impl Into<String>, line: usize, column: usize) -> RuntimeValue {
+    layout(
+        "JsonError",
+        vec![
+            ("message", RuntimeValue::String(message.into())),
+            ("line", RuntimeValue::Number(line as f64)),
+            ("column", RuntimeValue::Number(column as f64)),
+        ],
+    )
+}
+
+fn stringify(name: &'static str, pretty: bool) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>json~>{}", name), move |args| {
+        match value_to_json(arg(&args, 0, name)) {
+            Ok(v) => RuntimeValue::String(v.to_json_string(pretty)),
+            Err(e) => json_error(format!("Cannot convert to JSON: {}", e), 0, 0),
+        }
+    })
+}
+
+/// Any layout works as an object, not only `JsonObject`.
+fn object_arg<'a>(args: &'a [RuntimeValue], function: &str) -> &'a LayoutData {
+    match arg(args, 0, function) {
+        RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) => v,
+        v => {
+            err!(intrp "Argument 1 of `{}` must be an object, got `{}`.", function, v);
+            exit(100)
+        }
+    }
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        MoSaBinding::new("std~>json~>parse", |args| {
+            match json::parse(str_arg(&args, 0, "parse")) {
+                Ok(v) => json_to_value(&v),
+                Err(e) => json_error(e.message, e.line, e.column),
+            }
+        }),
+        stringify("stringify", false),
+        stringify("pretty", true),
+        MoSaBinding::new("std~>json~>object", |_| layout("JsonObject", vec![])),
+        MoSaBinding::new("std~>json~>keys", |args| {
+            let object = object_arg(&args, "keys");
+            let keys: Vec<_> = object.entries.read().unwrap().keys().cloned().collect();
+
+            to_iterable(keys.into_iter().map(RuntimeValue::String))
+        }),
+        MoSaBinding::new("std~>json~>hasKey", |args| {
+            let object = object_arg(&args, "hasKey");
+            let key = str_arg(&args, 1, "hasKey");
+
+            RuntimeValue::Bool(object.entries.read().unwrap().contains_key(key))
+        }),
+        MoSaBinding::new("std~>json~>get", |args| {
+            let object = object_arg(&args, "get");
+            let key = str_arg(&args, 1, "get");
+
+            object.entries.read().unwrap().get(key).cloned().unwrap_or(RuntimeValue::Null)
+        }),
+        MoSaBinding::new("std~>json~>set", |args| {
+            let object = object_arg(&args, "set");
+            let key = str_arg(&args, 1, "set").clone();
+            let value = arg(&args, 2, "set").clone();
+
+            object.entries.write().unwrap().insert(key, value);
+
+            RuntimeValue::Null
+        }),
+        MoSaBinding::new("std~>json~>remove", |args| {
+            let object = object_arg(&args, "remove");
+            let key = str_arg(&args, 1, "remove");
+
+            object.entries.write().unwrap().shift_remove(key).unwrap_or(RuntimeValue::Null)
+        }),
+    ]
+}
@@ -0,0 +1,103 @@
+use crate::err;
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::num_arg;
+use crate::wrapper::MoSaBinding;
+use std::process::exit;
+
+fn unary(name: &'static str, f: fn(f64) -> f64) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>math~>{}", name), move |args| {
+        RuntimeValue::Number(f(num_arg(&args, 0, name)))
+    })
+}
+
+fn binary(name: &'static str, f: fn(f64, f64) -> f64) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>math~>{}", name), move |args| {
+        RuntimeValue::Number(f(num_arg(&args, 0, name), num_arg(&args, 1, name)))
+    })
+}
+
+fn check(name: &'static str, f: fn(f64) -> bool) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>math~>{}", name), move |args| {
+        RuntimeValue::Bool(f(num_arg(&args, 0, name)))
+    })
+}
+
+/// `min` and `max` take any amount of numbers.
+fn fold(name: &'static str, f: fn(f64, f64) -> f64) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>math~>{}", name), move |args| {
+        let mut res = num_arg(&args, 0, name);
+
+        for index in 1..args.len() {
+            res = f(res, num_arg(&args, index, name));
+        }
+
+        RuntimeValue::Number(res)
+    })
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        unary("abs", f64::abs),
+        unary("floor", f64::floor),
+        unary("ceil", f64::ceil),
+        unary("round", f64::round),
+        unary("trunc", f64::trunc),
+        unary("fract", f64::fract),
+        unary("sign", |v| if v == 0.0 || v.is_nan() { v } else { v.signum() }),
+        unary("sqrt", f64::sqrt),
+        unary("cbrt", f64::cbrt),
+        unary("ePow", f64::exp),
+        unary("ln", f64::ln),
+        unary("log2", f64::log2),
+        unary("log10", f64::log10),
+        unary("sin", f64::sin),
+        unary("cos", f64::cos),
+        unary("tan", f64::tan),
+        unary("asin", f64::asin),
+        unary("acos", f64::acos),
+        unary("atan", f64::atan),
+        unary("sinh", f64::sinh),
+        unary("cosh", f64::cosh),
+        unary("tanh", f64::tanh),
+        unary("toRadians", f64::to_radians),
+        unary("toDegrees", f64::to_degrees),
+        binary("pow", f64::powf),
+        binary("log", f64::log),
+        binary("atan2", f64::atan2),
+        binary("hypot", f64::hypot),
+        fold("min", f64::min),
+        fold("max", f64::max),
+        MoSaBinding::new("std~>math~>clamp", |args| {
+            let v = num_arg(&args, 0, "clamp");
+            let min = num_arg(&args, 1, "clamp");
+            let max = num_arg(&args, 2, "clamp");
+
+            if min.is_nan() || max.is_nan() {
+                err!(intrp "`clamp` cannot clamp to a NaN bound, got {} and {}.", min, max);
+                exit(100)
+            }
+
+            if min > max {
+                err!(intrp "`clamp` got a minimum ({}) bigger than the maximum ({}).", min, max);
+                exit(100)
+            }
+
+            RuntimeValue::Number(v.clamp(min, max))
+        }),
+        MoSaBinding::new("std~>math~>idiv", |args| {
+            let l = num_arg(&args, 0, "idiv");
+            let r = num_arg(&args, 1, "idiv");
+
+            if r == 0.0 {
+                err!(intrp "Integer division by zero.");
+                exit(100)
+            }
+
+            RuntimeValue::Number((l / r).floor())
+        }),
+        check("isNaN", f64::is_nan),
+        check("isInfinite", f64::is_infinite),
+        check("isFinite", f64::is_finite),
+        check("isInt", |v| v.is_finite() && v.fract() == 0.0),
+    ]
+}
@@ -0,0 +1,278 @@
+pub mod env;
+pub mod fs;
+pub mod io;
+pub mod iter;
+pub mod json;
+pub mod math;
+pub mod process;
+pub mod random;
+pub mod regex;
+pub mod result;
+pub mod str;
+pub mod time;
+
+use crate::err;
+use crate::global::DataType;
+use crate::interpreter::scope::FunctionData;
+use crate::interpreter::structs::{
+    call_function, IterablePair, LayoutData, Reference, RuntimeValue,
+};
+use crate::modules::resolver::{
+    ModuleRequest, ModuleResolver, ResolveError, ResolvedModule, StaticResolver,
+};
+use crate::stdlib::time::{Clock, SystemClock};
+use crate::wrapper::MoSaBinding;
+use std::path::PathBuf;
+use std::process::exit;
+use std::sync::Arc;
+
+/// The modules of `@std`, compiled in so scripts can use them without registering `lib/std`.
+pub static MODULES: &[(&str, &str)] = &[
+    ("@std:lib", include_str!("../../lib/std/lib.mosa")),
+    ("@std:math", include_str!("../../lib/std/math.mosa")),
+    ("@std:str", include_str!("../../lib/std/str.mosa")),
+    ("@std:io", include_str!("../../lib/std/io.mosa")),
+    ("@std:fs", include_str!("../../lib/std/fs.mosa")),
+    ("@std:json", include_str!("../../lib/std/json.mosa")),
+    ("@std:time", include_str!("../../lib/std/time.mosa")),
+    ("@std:random", include_str!("../../lib/std/random.mosa")),
+    ("@std:regex", include_str!("../../lib/std/regex.mosa")),
+    ("@std:env", include_str!("../../lib/std/env.mosa")),
+    ("@std:process", include_str!("../../lib/std/process.mosa")),
+    ("@std:iter", include_str!("../../lib/std/iter.mosa")),
+    ("@std:error", include_str!("../../lib/std/error.mosa")),
+    ("@std:result", include_str!("../../lib/std/result.mosa")),
+];
+
+/// What the host lets the standard library do.
+#[derive(Clone)]
+pub struct StdOptions {
+    /// `std:fs` can only access files inside these directories, relative paths start in the first one
+    pub fs_roots: Vec<PathBuf>,
+    pub clock: Arc<dyn Clock>,
+    /// seeds the default generator of `std:random`, otherwise it is seeded randomly
+    pub random_seed: Option<u64>,
+    /// enables `std:env`
+    pub env: bool,
+    /// returned by `args` of `std:env`
+    pub args: Vec<String>,
+    /// enables `std:process`
+    pub process: bool,
+}
+
+impl Default for StdOptions {
+    fn default() -> Self {
+        Self {
+            fs_roots: vec![],
+            clock: Arc::new(SystemClock::new()),
+            random_seed: None,
+            env: false,
+            args: vec![],
+            process: false,
+        }
+    }
+}
+
+/// The natives behind `@std`, `MoSaRunner` registers them before its own bindings.
+pub fn bindings(options: &StdOptions) -> Vec<MoSaBinding> {
+    let mut bindings = vec![];
+
+    bindings.append(&mut math::bindings());
+    bindings.append(&mut str::bindings());
+    bindings.append(&mut io::bindings());
+    bindings.append(&mut fs::bindings(options.fs_roots.clone()));
+    bindings.append(&mut json::bindings());
+    bindings.append(&mut time::bindings(options.clock.clone()));
+    bindings.append(&mut random::bindings(options.random_seed));
+    bindings.append(&mut regex::bindings());
+    bindings.append(&mut env::bindings(options.env, options.args.clone()));
+    bindings.append(&mut process::bindings(options.process));
+    bindings.append(&mut iter::bindings());
+    bindings.append(&mut result::bindings());
+
+    bindings
+}
+
+/// Uses the compiled in `@std` modules when `inner` can't find them,
+/// so a registered `std` library can still add or replace modules.
+pub struct StdResolver {
+    inner: Arc<dyn ModuleResolver>,
+    std: StaticResolver,
+}
+
+impl StdResolver {
+    pub fn new(inner: Arc<dyn ModuleResolver>) -> Self {
+        Self {
+            inner,
+            std: StaticResolver::new(MODULES),
+        }
+    }
+}
+
+impl ModuleResolver for StdResolver {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError> {
+        match self.inner.resolve(request) {
+            Err(ResolveError::NotFound(_) | ResolveError::UnknownLib(_))
+                if request.lib.as_deref() == Some("std") =>
+            {
+                self.std.resolve(request)
+            }
+            res => res,
+        }
+    }
+}
+
+pub(crate) fn arg<'a>(args: &'a [RuntimeValue], index: usize, function: &str) -> &'a RuntimeValue {
+    match args.get(index) {
+        Some(v) => v,
+        None => {
+            err!(intrp "`{}` expects at least {} arguments, got {}.", function, index + 1, args.len());
+            exit(100)
+        }
+    }
+}
+
+pub(crate) fn num_arg(args: &[RuntimeValue], index: usize, function: &str) -> f64 {
+    match arg(args, index, function) {
+        RuntimeValue::Number(v) => *v,
+        v => {
+            err!(intrp "Argument {} of `{}` must be a number, got `{}`.", index + 1, function, v);
+            exit(100)
+        }
+    }
+}
+
+pub(crate) fn str_arg<'a>(args: &'a [RuntimeValue], index: usize, function: &str) -> &'a String {
+    match arg(args, index, function) {
+        RuntimeValue::String(v) => v,
+        v => {
+            err!(intrp "Argument {} of `{}` must be a string, got `{}`.", index + 1, function, v);
+            exit(100)
+        }
+    }
+}
+
+/// The values of an iterable or a range, ranges are pulled one by one.
+pub(crate) fn iterable_arg(
+    args: &[RuntimeValue],
+    index: usize,
+    function: &str,
+) -> Box<dyn Iterator<Item = RuntimeValue>> {
+    let v = arg(args, index, function);
+
+    match v.iter_values() {
+        Some(values) => values,
+        None => {
+            err!(intrp "Argument {} of `{}` must be an iterable, got `{}`.", index + 1, function, v);
+            exit(100)
+        }
+    }
+}
+
+/// The collected values of an iterable or a range, infinite ranges are refused.
+pub(crate) fn list_arg(args: &[RuntimeValue], index: usize, function: &str) -> Vec<RuntimeValue> {
+    if let RuntimeValue::Range(range) = arg(args, index, function) {
+        if !range.end.is_finite() {
+            err!(intrp "`{}` cannot collect the infinite range `{}`, use `take` first.", function, args[index]);
+            exit(100)
+        }
+    }
+
+    iterable_arg(args, index, function).collect()
+}
+
+/// Natives take numbers as `f64`, indexes and counts must be whole and not negative.
+pub(crate) fn index_arg(args: &[RuntimeValue], index: usize, function: &str) -> usize {
+    let v = num_arg(args, index, function);
+
+    if v < 0.0 || v.fract() != 0.0 || !v.is_finite() {
+        err!(intrp "Argument {} of `{}` must be a whole number that isn't negative, got `{}`.", index + 1, function, v);
+        exit(100)
+    }
+
+    v as usize
+}
+
+pub(crate) fn to_iterable(values: impl IntoIterator<Item = RuntimeValue>) -> RuntimeValue {
+    RuntimeValue::Iterable(
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| IterablePair { index, value })
+            .collect(),
+    )
+}
+
+/// Creates a value of a layout declared in a `@std` module, e.g. `IoError`.
+pub(crate) fn layout(layout_id: &str, fields: Vec<(&str, RuntimeValue)>) -> RuntimeValue {
+    let entries = fields
+        .into_iter()
+        .map(|(field, value)| (field.to_string(), value))
+        .collect();
+
+    LayoutData::new(layout_id.to_string(), entries, Default::default()).into_value()
+}
+
+/// A function taking `arity` arguments, its return type is checked before it's called.
+pub(crate) fn function_arg(
+    args: &[RuntimeValue],
+    index: usize,
+    arity: usize,
+    returns: Option<DataType>,
+    function: &str,
+) -> FunctionData {
+    let fd = match arg(args, index, function) {
+        RuntimeValue::Reference(Reference::Function(fd)) => fd.clone(),
+        v => {
+            err!(intrp "Argument {} of `{}` must be a function, got `{}`.", index + 1, function, v);
+            exit(100)
+        }
+    };
+
+    if fd.args.len() != arity {
+        let plural = if arity == 1 { "" } else { "s" };
+
+        err!(intrp "`{}` expects a function with {} argument{}, got one with {}.", function, arity, plural, fd.args.len());
+        exit(100)
+    }
+
+    if let Some(returns) = returns {
+        if fd.return_type != returns {
+            err!(intrp "`{}` expects a function returning `{}`, got one returning `{}`.", function, returns, fd.return_type);
+            exit(100)
+        }
+    }
+
+    fd
+}
+
+/// Calls `fd` after checking the arguments against its argument types,
+/// so a function for the wrong items fails with the item that didn't match.
+/// `item` is the index of the item being passed, if there is one.
+pub(crate) fn call(fd: &FunctionData, values: Vec<RuntimeValue>, item: Option<usize>, function: &str) -> RuntimeValue {
+    {
+        let scope = fd.scope.read().unwrap();
+
+        for ((name, data_type), value) in fd.args.iter().zip(&values) {
+            let value_type = scope.get_value_type(value);
+
+            if !scope.type_matches(data_type, &value_type) {
+                let item = match item {
+                    Some(index) => format!("item {}", index),
+                    None => "a value".to_string(),
+                };
+
+                err!(intrp "`{}` cannot pass {} of type `{}` to argument `{}` of type `{}`.", function, item, value_type, name, data_type);
+                exit(100)
+            }
+        }
+    }
+
+    match call_function(fd.clone(), values) {
+        Some(v) => v,
+        None => {
+            err!(intrp "`{}` can only call functions while a script is running.", function);
+            exit(100)
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::io::{from_io_error, not_granted};
+use crate::stdlib::{layout, list_arg, num_arg, str_arg};
+use crate::wrapper::MoSaBinding;
+use std::io::Write;
+use std::process::Command;
+
+fn binding(
+    name: &'static str,
+    enabled: bool,
+    f: impl Fn(&[RuntimeValue]) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>process~>{}", name), move |args| {
+        if !enabled {
+            return not_granted("std:process", "grant_process");
+        }
+
+        f(&args)
+    })
+}
+
+pub fn bindings(enabled: bool) -> Vec<MoSaBinding> {
+    vec![
+        binding("run", enabled, |args| {
+            let program = str_arg(args, 0, "run");
+            let mut command = Command::new(program);
+
+            if args.get(1).is_some() {
+                command.args(list_arg(args, 1, "run").iter().map(|v| v.to_string()));
+            }
+
+            match command.output() {
+                Ok(output) => layout(
+                    "Output",
+                    vec![
+                        // `null` when the command was stopped by a signal
+                        ("code", match output.status.code() {
+                            Some(code) => RuntimeValue::Number(code as f64),
+                            None => RuntimeValue::Null,
+                        }),
+                        ("success", RuntimeValue::Bool(output.status.success())),
+                        ("stdout", RuntimeValue::String(String::from_utf8_lossy(&output.stdout).to_string())),
+                        ("stderr", RuntimeValue::String(String::from_utf8_lossy(&output.stderr).to_string())),
+                    ],
+                ),
+                Err(e) => from_io_error(&e, &format!("Cannot run `{}`", program)),
+            }
+        }),
+        binding("exit", enabled, |args| {
+            let code = match args.first() {
+                Some(_) => num_arg(args, 0, "exit") as i32,
+                None => 0,
+            };
+
+            let _ = std::io::stdout().flush();
+            let _ = std::io::stderr().flush();
+
+            std::process::exit(code)
+        }),
+    ]
+}
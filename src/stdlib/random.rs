@@ -0,0 +1,220 @@
+use crate::err;
+use crate::interpreter::structs::{ComplexRuntimeValue, RuntimeValue};
+use crate::stdlib::{list_arg, num_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::collections::HashMap;
+use std::process::exit;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// xoshiro256**, small and fast, seeded through SplitMix64 so any seed gives a usable state.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    state: [u64; 4],
+}
+
+impl Generator {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+
+        Self {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    pub fn from_entropy() -> Self {
+        Self::from_seed(Uuid::new_v4().as_u64_pair().0)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let res = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        res
+    }
+
+    /// In `0..1`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// In `0..bound`, without the bias of a plain modulo.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let v = self.next_u64();
+
+            if v < zone {
+                return v % bound;
+            }
+        }
+    }
+}
+
+/// The generators of one runner, `0` is the default one used without a `Random`.
+struct Generators {
+    next_id: u64,
+    generators: HashMap<u64, Generator>,
+}
+
+type SharedGenerators = Arc<Mutex<Generators>>;
+
+const MAX_SPAN: f64 = (1u64 << 53) as f64;
+
+fn binding(
+    name: &'static str,
+    generators: &SharedGenerators,
+    generator_index: usize,
+    f: impl Fn(&[RuntimeValue], &mut Generator) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    let generators = generators.clone();
+
+    MoSaBinding::new(format!("std~>random~>{}", name), move |args| {
+        let id = generator_id(&args, generator_index, name);
+        let generator = generators.lock().unwrap().generators.get(&id).cloned();
+
+        let Some(mut generator) = generator else {
+            err!(intrp "`{}` got a `Random` that doesn't exist.", name);
+            exit(100)
+        };
+
+        // `f` runs on a copy, so the lock isn't held if it throws, and a failed call doesn't advance the generator
+        let res = f(&args, &mut generator);
+
+        generators.lock().unwrap().generators.insert(id, generator);
+
+        res
+    })
+}
+
+/// The optional last argument, a `Random`.
+fn generator_id(args: &[RuntimeValue], index: usize, function: &str) -> u64 {
+    match args.get(index) {
+        None => 0,
+        Some(RuntimeValue::Complex(ComplexRuntimeValue::Layout(v))) if v.layout_id == "Random" => {
+            match v.entries.read().unwrap().get("id") {
+                Some(RuntimeValue::Number(id)) => *id as u64,
+                _ => 0,
+            }
+        }
+        Some(v) => {
+            err!(intrp "Argument {} of `{}` must be a `Random`, got `{}`.", index + 1, function, v);
+            exit(100)
+        }
+    }
+}
+
+fn seed_from(v: f64) -> u64 {
+    if v.fract() == 0.0 && v.abs() < u64::MAX as f64 {
+        v as i64 as u64
+    } else {
+        v.to_bits()
+    }
+}
+
+/// `seed` makes the default generator reproducible too.
+pub fn bindings(seed: Option<u64>) -> Vec<MoSaBinding> {
+    let default = match seed {
+        Some(seed) => Generator::from_seed(seed),
+        None => Generator::from_entropy(),
+    };
+
+    let generators: SharedGenerators = Arc::new(Mutex::new(Generators {
+        next_id: 1,
+        generators: HashMap::from([(0, default)]),
+    }));
+
+    let created = generators.clone();
+
+    vec![
+        MoSaBinding::new("std~>random~>createGenerator", move |args| {
+            let generator = match args.first() {
+                Some(_) => Generator::from_seed(seed_from(num_arg(&args, 0, "seeded"))),
+                None => Generator::from_entropy(),
+            };
+            let mut generators = created.lock().unwrap();
+            let id = generators.next_id;
+
+            generators.next_id += 1;
+            generators.generators.insert(id, generator);
+
+            RuntimeValue::Number(id as f64)
+        }),
+        binding("random", &generators, 0, |_, g| RuntimeValue::Number(g.next_f64())),
+        binding("range", &generators, 2, |args, g| {
+            let min = num_arg(args, 0, "range");
+            let max = num_arg(args, 1, "range");
+
+            if min > max {
+                err!(intrp "`range` got a minimum ({}) bigger than the maximum ({}).", min, max);
+                exit(100)
+            }
+
+            RuntimeValue::Number(min + g.next_f64() * (max - min))
+        }),
+        binding("integer", &generators, 2, |args, g| {
+            let min = num_arg(args, 0, "integer").ceil();
+            let max = num_arg(args, 1, "integer").floor();
+
+            if min > max || !min.is_finite() || !max.is_finite() {
+                err!(intrp "`integer` has no whole numbers between {} and {}.", min, max);
+                exit(100)
+            }
+
+            // above 2^53 not every whole number is a `num`
+            if max - min >= MAX_SPAN {
+                err!(intrp "`integer` cannot pick from more than 2^53 whole numbers, got {} to {}.", min, max);
+                exit(100)
+            }
+
+            let span = (max - min) as u64 + 1;
+
+            RuntimeValue::Number(min + g.below(span) as f64)
+        }),
+        binding("chance", &generators, 1, |args, g| {
+            RuntimeValue::Bool(g.next_f64() < num_arg(args, 0, "chance"))
+        }),
+        binding("choose", &generators, 1, |args, g| {
+            let items = list_arg(args, 0, "choose");
+
+            if items.is_empty() {
+                return RuntimeValue::Null;
+            }
+
+            items[g.below(items.len() as u64) as usize].clone()
+        }),
+        binding("shuffle", &generators, 1, |args, g| {
+            let mut items = list_arg(args, 0, "shuffle");
+
+            for i in (1..items.len()).rev() {
+                items.swap(i, g.below(i as u64 + 1) as usize);
+            }
+
+            to_iterable(items)
+        }),
+        binding("uuid", &generators, 0, |_, g| {
+            let mut bytes = [0u8; 16];
+
+            bytes[..8].copy_from_slice(&g.next_u64().to_le_bytes());
+            bytes[8..].copy_from_slice(&g.next_u64().to_le_bytes());
+
+            RuntimeValue::String(uuid::Builder::from_random_bytes(bytes).into_uuid().to_string())
+        }),
+    ]
+}
@@ -0,0 +1,201 @@
+use crate::err;
+use crate::interpreter::structs::{ComplexRuntimeValue, RuntimeValue};
+use crate::stdlib::{arg, layout, str_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use regex::Regex;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::process::exit;
+use std::sync::{Arc, Mutex};
+
+/// Compiled patterns of one runner, `Regex` layouts keep the index, plain strings are cached by pattern.
+/// Patterns of `compile` are kept for as long as the runner, compiling the same pattern again gives the same index.
+#[derive(Default)]
+struct Patterns {
+    compiled: Vec<Regex>,
+    compiled_ids: HashMap<String, usize>,
+    /// the least recently used pattern is first
+    cache: IndexMap<String, Regex>,
+}
+
+/// How many plain string patterns are cached.
+const CACHE_SIZE: usize = 64;
+
+type SharedPatterns = Arc<Mutex<Patterns>>;
+
+fn compile(pattern: &str, function: &str) -> Regex {
+    match Regex::new(pattern) {
+        Ok(v) => v,
+        Err(e) => {
+            err!(intrp "`{}` got an invalid pattern `{}`: {}", function, pattern, e);
+            exit(100)
+        }
+    }
+}
+
+/// The first argument, a `Regex` or a pattern.
+/// Patterns are compiled without holding the lock, so an invalid one doesn't poison it when the error is caught.
+fn regex_arg(patterns: &SharedPatterns, args: &[RuntimeValue], function: &str) -> Regex {
+    match arg(args, 0, function) {
+        RuntimeValue::String(pattern) => {
+            {
+                let mut patterns = patterns.lock().unwrap();
+
+                if let Some(index) = patterns.cache.get_index_of(pattern) {
+                    let last = patterns.cache.len() - 1;
+
+                    patterns.cache.move_index(index, last);
+                    return patterns.cache[last].clone();
+                }
+            }
+
+            let re = compile(pattern, function);
+            let mut patterns = patterns.lock().unwrap();
+
+            if patterns.cache.len() == CACHE_SIZE {
+                patterns.cache.shift_remove_index(0);
+            }
+
+            patterns.cache.insert(pattern.clone(), re.clone());
+            re
+        }
+        RuntimeValue::Complex(ComplexRuntimeValue::Layout(v)) if v.layout_id == "Regex" => {
+            let id = match v.entries.read().unwrap().get("id") {
+                Some(RuntimeValue::Number(id)) => *id as usize,
+                _ => usize::MAX,
+            };
+
+            let re = patterns.lock().unwrap().compiled.get(id).cloned();
+
+            match re {
+                Some(re) => re,
+                None => {
+                    err!(intrp "`{}` got a `Regex` that wasn't created with `compile`.", function);
+                    exit(100)
+                }
+            }
+        }
+        v => {
+            err!(intrp "Argument 1 of `{}` must be a `Regex` or a pattern, got `{}`.", function, v);
+            exit(100)
+        }
+    }
+}
+
+fn char_index(text: &str, byte_offset: usize) -> f64 {
+    text[..byte_offset].chars().count() as f64
+}
+
+/// A `Match` with char indexes, `groups` has `null` for groups that didn't take part.
+fn to_match(re: &Regex, text: &str, captures: &regex::Captures) -> RuntimeValue {
+    let whole = captures.get(0).unwrap();
+
+    let groups = to_iterable(captures.iter().map(|group| match group {
+        Some(g) => RuntimeValue::String(g.as_str().to_string()),
+        None => RuntimeValue::Null,
+    }));
+
+    let named = re
+        .capture_names()
+        .flatten()
+        .map(|name| {
+            let value = match captures.name(name) {
+                Some(g) => RuntimeValue::String(g.as_str().to_string()),
+                None => RuntimeValue::Null,
+            };
+
+            (name, value)
+        })
+        .collect();
+
+    layout(
+        "Match",
+        vec![
+            ("text", RuntimeValue::String(whole.as_str().to_string())),
+            ("start", RuntimeValue::Number(char_index(text, whole.start()))),
+            ("end", RuntimeValue::Number(char_index(text, whole.end()))),
+            ("groups", groups),
+            ("named", layout("Captures", named)),
+        ],
+    )
+}
+
+fn binding(
+    name: &'static str,
+    patterns: &SharedPatterns,
+    f: impl Fn(&[RuntimeValue], Regex) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    let patterns = patterns.clone();
+
+    MoSaBinding::new(format!("std~>regex~>{}", name), move |args| {
+        let re = regex_arg(&patterns, &args, name);
+
+        f(&args, re)
+    })
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    let patterns: SharedPatterns = Arc::default();
+    let compiled = patterns.clone();
+
+    vec![
+        MoSaBinding::new("std~>regex~>compileId", move |args| {
+            let pattern = str_arg(&args, 0, "compile");
+
+            if let Some(id) = compiled.lock().unwrap().compiled_ids.get(pattern) {
+                return RuntimeValue::Number(*id as f64);
+            }
+
+            let re = compile(pattern, "compile");
+            let mut patterns = compiled.lock().unwrap();
+            let id = patterns.compiled.len();
+
+            patterns.compiled.push(re);
+            patterns.compiled_ids.insert(pattern.clone(), id);
+
+            RuntimeValue::Number(id as f64)
+        }),
+        MoSaBinding::new("std~>regex~>validate", |args| {
+            match Regex::new(str_arg(&args, 0, "validate")) {
+                Ok(_) => RuntimeValue::Null,
+                Err(e) => RuntimeValue::String(e.to_string()),
+            }
+        }),
+        MoSaBinding::new("std~>regex~>escape", |args| {
+            RuntimeValue::String(regex::escape(str_arg(&args, 0, "escape")))
+        }),
+        binding("isMatch", &patterns, |args, re| {
+            RuntimeValue::Bool(re.is_match(str_arg(args, 1, "isMatch")))
+        }),
+        binding("find", &patterns, |args, re| {
+            let text = str_arg(args, 1, "find");
+
+            match re.captures(text) {
+                Some(captures) => to_match(&re, text, &captures),
+                None => RuntimeValue::Null,
+            }
+        }),
+        binding("findAll", &patterns, |args, re| {
+            let text = str_arg(args, 1, "findAll");
+
+            to_iterable(re.captures_iter(text).map(|c| to_match(&re, text, &c)))
+        }),
+        binding("replace", &patterns, |args, re| {
+            let text = str_arg(args, 1, "replace");
+            let replacement = str_arg(args, 2, "replace");
+
+            RuntimeValue::String(re.replace_all(text, replacement.as_str()).to_string())
+        }),
+        binding("replaceFirst", &patterns, |args, re| {
+            let text = str_arg(args, 1, "replaceFirst");
+            let replacement = str_arg(args, 2, "replaceFirst");
+
+            RuntimeValue::String(re.replace(text, replacement.as_str()).to_string())
+        }),
+        binding("split", &patterns, |args, re| {
+            let text = str_arg(args, 1, "split");
+
+            to_iterable(re.split(text).map(|v| RuntimeValue::String(v.to_string())))
+        }),
+    ]
+}
@@ -0,0 +1,66 @@
+use crate::err;
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::{arg, call, function_arg};
+use crate::wrapper::MoSaBinding;
+use std::process::exit;
+
+type ResultValue = Result<Box<RuntimeValue>, Box<RuntimeValue>>;
+
+fn binding(name: &'static str, f: impl Fn(&[RuntimeValue], &ResultValue) -> RuntimeValue + 'static) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>result~>{}", name), move |args| {
+        let result = result_arg(&args, name);
+
+        f(&args, &result)
+    })
+}
+
+/// The first argument, an `Ok` or an `Err`.
+fn result_arg(args: &[RuntimeValue], function: &str) -> ResultValue {
+    match arg(args, 0, function) {
+        RuntimeValue::Result(v) => v.clone(),
+        v => {
+            err!(intrp "Argument 1 of `{}` must be a result, got `{}`.", function, v);
+            exit(100)
+        }
+    }
+}
+
+/// Calls the function in argument 2 with the value of `Ok` or of `Err`, the other side is kept as it is.
+fn map(name: &'static str, ok: bool) -> MoSaBinding {
+    binding(name, move |args, result| {
+        let fd = function_arg(args, 1, 1, None, name);
+
+        match result {
+            Ok(v) if ok => RuntimeValue::Result(Ok(Box::new(call(&fd, vec![*v.clone()], None, name)))),
+            Err(e) if !ok => RuntimeValue::Result(Err(Box::new(call(&fd, vec![*e.clone()], None, name)))),
+            v => RuntimeValue::Result(v.clone()),
+        }
+    })
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        binding("isOk", |_, result| RuntimeValue::Bool(result.is_ok())),
+        binding("isErr", |_, result| RuntimeValue::Bool(result.is_err())),
+        binding("unwrap", |args, result| match result {
+            Ok(v) => *v.clone(),
+            Err(_) => {
+                err!(intrp "`unwrap` got `{}`.", args[0]);
+                exit(100)
+            }
+        }),
+        binding("unwrapErr", |args, result| match result {
+            Err(e) => *e.clone(),
+            Ok(_) => {
+                err!(intrp "`unwrapErr` got `{}`.", args[0]);
+                exit(100)
+            }
+        }),
+        binding("unwrapOr", |args, result| match result {
+            Ok(v) => *v.clone(),
+            Err(_) => arg(args, 1, "unwrapOr").clone(),
+        }),
+        map("map", true),
+        map("mapErr", false),
+    ]
+}
@@ -0,0 +1,234 @@
+use crate::err;
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::{arg, index_arg, list_arg, num_arg, str_arg, to_iterable};
+use crate::wrapper::MoSaBinding;
+use std::process::exit;
+
+// Every index is counted in chars (Unicode scalar values), not bytes.
+
+fn binding(
+    name: &'static str,
+    f: impl Fn(Vec<RuntimeValue>) -> RuntimeValue + 'static,
+) -> MoSaBinding {
+    MoSaBinding::new(format!("std~>str~>{}", name), f)
+}
+
+fn map(name: &'static str, f: fn(&str) -> String) -> MoSaBinding {
+    binding(name, move |args| RuntimeValue::String(f(str_arg(&args, 0, name))))
+}
+
+fn test(name: &'static str, f: fn(&str, &str) -> bool) -> MoSaBinding {
+    binding(name, move |args| {
+        RuntimeValue::Bool(f(str_arg(&args, 0, name), str_arg(&args, 1, name)))
+    })
+}
+
+fn byte_offset(s: &str, char_index: usize) -> usize {
+    s.char_indices().nth(char_index).map(|(i, _)| i).unwrap_or(s.len())
+}
+
+fn char_index(s: &str, byte_offset: usize) -> usize {
+    s[..byte_offset].chars().count()
+}
+
+/// Counts negative indexes from the end, and limits them to the string.
+fn relative_index(v: f64, len: usize) -> usize {
+    if v < 0.0 {
+        (len as f64 + v).max(0.0) as usize
+    } else {
+        (v as usize).min(len)
+    }
+}
+
+/// The longest string, in bytes, that repeating and padding can create.
+const MAX_LEN: usize = 1 << 28;
+
+/// Stops the script if `count` copies of something `len` bytes long would be longer than `MAX_LEN`.
+fn check_repeat(len: usize, count: usize, function: &str) {
+    if len.checked_mul(count).is_none_or(|v| v > MAX_LEN) {
+        err!(intrp "`{}` cannot create a string longer than {} bytes.", function, MAX_LEN);
+        exit(100)
+    }
+}
+
+fn pad(name: &'static str, start: bool) -> MoSaBinding {
+    binding(name, move |args| {
+        let s = str_arg(&args, 0, name);
+        let width = index_arg(&args, 1, name);
+        let fill = match args.get(2) {
+            Some(_) => str_arg(&args, 2, name).clone(),
+            None => " ".to_string(),
+        };
+
+        if fill.is_empty() {
+            err!(intrp "`{}` cannot pad with an empty string.", name);
+            exit(100)
+        }
+
+        let missing = width.saturating_sub(s.chars().count());
+        let widest = fill.chars().map(char::len_utf8).max().unwrap_or(1);
+
+        check_repeat(widest, missing, name);
+
+        let padding: String = fill.chars().cycle().take(missing).collect();
+
+        RuntimeValue::String(if start {
+            padding + s
+        } else {
+            s.clone() + &padding
+        })
+    })
+}
+
+pub fn bindings() -> Vec<MoSaBinding> {
+    vec![
+        binding("len", |args| {
+            RuntimeValue::Number(str_arg(&args, 0, "len").chars().count() as f64)
+        }),
+        binding("chars", |args| {
+            to_iterable(
+                str_arg(&args, 0, "chars")
+                    .chars()
+                    .map(|c| RuntimeValue::String(c.to_string())),
+            )
+        }),
+        binding("substring", |args| {
+            let s = str_arg(&args, 0, "substring");
+            let len = s.chars().count();
+            let start = index_arg(&args, 1, "substring");
+            let end = match args.get(2) {
+                Some(_) => index_arg(&args, 2, "substring"),
+                None => len,
+            };
+
+            if start > end || end > len {
+                err!(intrp "`substring` range {}..{} is out of bounds for a string of length {}.", start, end, len);
+                exit(100)
+            }
+
+            RuntimeValue::String(s[byte_offset(s, start)..byte_offset(s, end)].to_string())
+        }),
+        binding("slice", |args| {
+            let s = str_arg(&args, 0, "slice");
+            let len = s.chars().count();
+            let start = relative_index(num_arg(&args, 1, "slice"), len);
+            let end = match args.get(2) {
+                Some(_) => relative_index(num_arg(&args, 2, "slice"), len),
+                None => len,
+            };
+
+            if start >= end {
+                return RuntimeValue::String(String::new());
+            }
+
+            RuntimeValue::String(s[byte_offset(s, start)..byte_offset(s, end)].to_string())
+        }),
+        binding("split", |args| {
+            let s = str_arg(&args, 0, "split");
+            let separator = str_arg(&args, 1, "split");
+
+            if separator.is_empty() {
+                return to_iterable(s.chars().map(|c| RuntimeValue::String(c.to_string())));
+            }
+
+            to_iterable(s.split(separator.as_str()).map(|v| RuntimeValue::String(v.to_string())))
+        }),
+        binding("join", |args| {
+            let items = list_arg(&args, 0, "join");
+            let separator = match args.get(1) {
+                Some(_) => str_arg(&args, 1, "join").as_str(),
+                None => "",
+            };
+
+            RuntimeValue::String(
+                items
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(separator),
+            )
+        }),
+        map("trim", |s| s.trim().to_string()),
+        map("trimStart", |s| s.trim_start().to_string()),
+        map("trimEnd", |s| s.trim_end().to_string()),
+        map("upper", |s| s.to_uppercase()),
+        map("lower", |s| s.to_lowercase()),
+        test("startsWith", |s, v| s.starts_with(v)),
+        test("endsWith", |s, v| s.ends_with(v)),
+        test("contains", |s, v| s.contains(v)),
+        binding("find", |args| {
+            let s = str_arg(&args, 0, "find");
+
+            match s.find(str_arg(&args, 1, "find").as_str()) {
+                Some(offset) => RuntimeValue::Number(char_index(s, offset) as f64),
+                None => RuntimeValue::Null,
+            }
+        }),
+        binding("findLast", |args| {
+            let s = str_arg(&args, 0, "findLast");
+
+            match s.rfind(str_arg(&args, 1, "findLast").as_str()) {
+                Some(offset) => RuntimeValue::Number(char_index(s, offset) as f64),
+                None => RuntimeValue::Null,
+            }
+        }),
+        binding("replace", |args| {
+            let s = str_arg(&args, 0, "replace");
+            let from = str_arg(&args, 1, "replace");
+            let to = str_arg(&args, 2, "replace");
+
+            RuntimeValue::String(s.replace(from.as_str(), to))
+        }),
+        binding("replaceFirst", |args| {
+            let s = str_arg(&args, 0, "replaceFirst");
+            let from = str_arg(&args, 1, "replaceFirst");
+            let to = str_arg(&args, 2, "replaceFirst");
+
+            RuntimeValue::String(s.replacen(from.as_str(), to, 1))
+        }),
+        binding("repeatStr", |args| {
+            let s = str_arg(&args, 0, "repeatStr");
+            let count = index_arg(&args, 1, "repeatStr");
+
+            check_repeat(s.len(), count, "repeatStr");
+
+            RuntimeValue::String(s.repeat(count))
+        }),
+        pad("padStart", true),
+        pad("padEnd", false),
+        binding("parseNum", |args| {
+            match str_arg(&args, 0, "parseNum").trim().parse::<f64>() {
+                Ok(v) => RuntimeValue::Number(v),
+                Err(_) => RuntimeValue::Null,
+            }
+        }),
+        binding("charCode", |args| {
+            let s = str_arg(&args, 0, "charCode");
+            let index = match args.get(1) {
+                Some(_) => index_arg(&args, 1, "charCode"),
+                None => 0,
+            };
+
+            match s.chars().nth(index) {
+                Some(c) => RuntimeValue::Number(c as u32 as f64),
+                None => {
+                    err!(intrp "`charCode` index {} is out of bounds for a string of length {}.", index, s.chars().count());
+                    exit(100)
+                }
+            }
+        }),
+        binding("fromCharCode", |args| {
+            let code = arg(&args, 0, "fromCharCode");
+
+            let index = index_arg(&args, 0, "fromCharCode");
+
+            match u32::try_from(index).ok().and_then(char::from_u32) {
+                Some(c) => RuntimeValue::String(c.to_string()),
+                None => {
+                    err!(intrp "`{}` is not a valid char code.", code);
+                    exit(100)
+                }
+            }
+        }),
+    ]
+}
@@ -0,0 +1,376 @@
+use crate::interpreter::structs::RuntimeValue;
+use crate::stdlib::{layout, num_arg, str_arg};
+use crate::wrapper::MoSaBinding;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Where `std:time` gets the time from, `MoSaRunner::with_clock` replaces it, e.g. with a `FakeClock` in tests.
+pub trait Clock: Send + Sync {
+    /// Time since the clock was created, it never goes back.
+    fn monotonic(&self) -> Duration;
+    /// Time since the Unix epoch.
+    fn unix(&self) -> Duration;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn monotonic(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn unix(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A clock that only moves when it's told to, or when a script sleeps.
+/// Clones share the time, so the host can keep one to move it.
+#[derive(Clone, Debug, Default)]
+pub struct FakeClock {
+    state: Arc<Mutex<FakeClockState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeClockState {
+    monotonic: Duration,
+    unix: Duration,
+}
+
+impl FakeClock {
+    /// Starts at `unix_millis` after the Unix epoch.
+    pub fn new(unix_millis: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FakeClockState {
+                monotonic: Duration::ZERO,
+                unix: Duration::from_millis(unix_millis),
+            })),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+
+        state.monotonic += duration;
+        state.unix += duration;
+    }
+
+    /// Changes the wall clock only, like the system clock being adjusted.
+    pub fn set_unix(&self, unix_millis: u64) {
+        self.state.lock().unwrap().unix = Duration::from_millis(unix_millis);
+    }
+}
+
+impl Clock for FakeClock {
+    fn monotonic(&self) -> Duration {
+        self.state.lock().unwrap().monotonic
+    }
+
+    fn unix(&self) -> Duration {
+        self.state.lock().unwrap().unix
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    days_from_civil(year + i64::from(month == 12), month % 12 + 1, 1) - days_from_civil(year, month, 1)
+}
+
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millis: i64,
+}
+
+impl DateTime {
+    fn from_unix_millis(unix_millis: i64) -> Self {
+        let days = unix_millis.div_euclid(86_400_000);
+        let rest = unix_millis.rem_euclid(86_400_000);
+        let (year, month, day) = civil_from_days(days);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: rest / 3_600_000,
+            minute: rest / 60_000 % 60,
+            second: rest / 1000 % 60,
+            millis: rest % 1000,
+        }
+    }
+
+    fn to_unix_millis(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * 86_400_000
+            + self.hour * 3_600_000
+            + self.minute * 60_000
+            + self.second * 1000
+            + self.millis
+    }
+
+    fn is_valid(&self) -> bool {
+        (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && (0..24).contains(&self.hour)
+            && (0..60).contains(&self.minute)
+            && (0..60).contains(&self.second)
+            && (0..1000).contains(&self.millis)
+    }
+
+    fn to_iso(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second, self.millis
+        )
+    }
+}
+
+/// Parses `YYYY-MM-DD`, optionally followed by `THH:MM`, `:SS`, `.fff` and `Z` or an offset like `+02:00`.
+/// Times without an offset are UTC.
+fn parse_iso(text: &str) -> Option<i64> {
+    fn number(text: &str, from: usize, len: usize) -> Option<i64> {
+        let part = text.get(from..from + len)?;
+
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        part.parse().ok()
+    }
+
+    let text = text.trim();
+    let bytes = text.as_bytes();
+
+    if bytes.get(4) != Some(&b'-') || bytes.get(7) != Some(&b'-') {
+        return None;
+    }
+
+    let mut date = DateTime {
+        year: number(text, 0, 4)?,
+        month: number(text, 5, 2)?,
+        day: number(text, 8, 2)?,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        millis: 0,
+    };
+
+    let mut position = 10;
+    let mut offset = 0;
+
+    if matches!(bytes.get(position), Some(b'T' | b't' | b' ')) {
+        if bytes.get(position + 3) != Some(&b':') {
+            return None;
+        }
+
+        date.hour = number(text, position + 1, 2)?;
+        date.minute = number(text, position + 4, 2)?;
+        position += 6;
+
+        if bytes.get(position) == Some(&b':') {
+            date.second = number(text, position + 1, 2)?;
+            position += 3;
+
+            if bytes.get(position) == Some(&b'.') {
+                let digits = bytes[position + 1..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+
+                if digits == 0 {
+                    return None;
+                }
+
+                // only milliseconds are kept
+                let fraction = &text[position + 1..position + 1 + digits.min(3)];
+                date.millis = fraction.parse::<i64>().ok()? * 10_i64.pow(3 - fraction.len() as u32);
+                position += 1 + digits;
+            }
+        }
+
+        match bytes.get(position) {
+            Some(b'Z' | b'z') => position += 1,
+            Some(sign @ (b'+' | b'-')) => {
+                if bytes.get(position + 3) != Some(&b':') {
+                    return None;
+                }
+
+                let minutes = number(text, position + 1, 2)? * 60 + number(text, position + 4, 2)?;
+                offset = if *sign == b'+' { minutes } else { -minutes };
+                position += 6;
+            }
+            _ => {}
+        }
+    }
+
+    if position != text.len() || !date.is_valid() {
+        return None;
+    }
+
+    Some(date.to_unix_millis() - offset * 60_000)
+}
+
+/// `250ms`, `1.5s` or `1h 2m 3.5s`
+fn format_duration(millis: f64) -> String {
+    if !millis.is_finite() || millis.abs() < 1000.0 {
+        return format!("{}ms", millis);
+    }
+
+    let sign = if millis < 0.0 { "-" } else { "" };
+    let millis = millis.abs();
+    let hours = (millis / 3_600_000.0).floor();
+    let minutes = (millis % 3_600_000.0 / 60_000.0).floor();
+    let seconds = millis % 60_000.0 / 1000.0;
+
+    let mut parts = vec![];
+
+    if hours > 0.0 {
+        parts.push(format!("{}h", hours));
+    }
+
+    if minutes > 0.0 {
+        parts.push(format!("{}m", minutes));
+    }
+
+    if seconds > 0.0 || parts.is_empty() {
+        parts.push(format!("{}s", seconds));
+    }
+
+    format!("{}{}", sign, parts.join(" "))
+}
+
+fn millis_arg(args: &[RuntimeValue], index: usize, function: &str) -> i64 {
+    num_arg(args, index, function).floor() as i64
+}
+
+pub fn bindings(clock: Arc<dyn Clock>) -> Vec<MoSaBinding> {
+    let monotonic = clock.clone();
+    let unix = clock.clone();
+    let sleep = clock;
+
+    vec![
+        MoSaBinding::new("std~>time~>now", move |_| {
+            RuntimeValue::Number(monotonic.monotonic().as_secs_f64() * 1000.0)
+        }),
+        MoSaBinding::new("std~>time~>unixMillis", move |_| {
+            RuntimeValue::Number(unix.unix().as_millis() as f64)
+        }),
+        MoSaBinding::new("std~>time~>sleepMillis", move |args| {
+            let millis = num_arg(&args, 0, "sleep");
+
+            if millis > 0.0 && millis.is_finite() {
+                sleep.sleep(Duration::from_secs_f64(millis / 1000.0));
+            }
+
+            RuntimeValue::Null
+        }),
+        MoSaBinding::new("std~>time~>formatDuration", |args| {
+            RuntimeValue::String(format_duration(num_arg(&args, 0, "toStr")))
+        }),
+        MoSaBinding::new("std~>time~>formatIso", |args| {
+            RuntimeValue::String(DateTime::from_unix_millis(millis_arg(&args, 0, "formatIso")).to_iso())
+        }),
+        MoSaBinding::new("std~>time~>parseIso", |args| {
+            match parse_iso(str_arg(&args, 0, "parseIso")) {
+                Some(v) => RuntimeValue::Number(v as f64),
+                None => RuntimeValue::Null,
+            }
+        }),
+        MoSaBinding::new("std~>time~>date", |args| {
+            let unix_millis = millis_arg(&args, 0, "date");
+            let date = DateTime::from_unix_millis(unix_millis);
+            // 1970-01-01 was a Thursday, weekdays go from 1 (Monday) to 7 (Sunday)
+            let weekday = (unix_millis.div_euclid(86_400_000) + 3).rem_euclid(7) + 1;
+
+            layout(
+                "DateTime",
+                vec![
+                    ("year", RuntimeValue::Number(date.year as f64)),
+                    ("month", RuntimeValue::Number(date.month as f64)),
+                    ("day", RuntimeValue::Number(date.day as f64)),
+                    ("hour", RuntimeValue::Number(date.hour as f64)),
+                    ("minute", RuntimeValue::Number(date.minute as f64)),
+                    ("second", RuntimeValue::Number(date.second as f64)),
+                    ("millis", RuntimeValue::Number(date.millis as f64)),
+                    ("weekday", RuntimeValue::Number(weekday as f64)),
+                ],
+            )
+        }),
+        MoSaBinding::new("std~>time~>fromDate", |args| {
+            let part = |index: usize, default: i64| match args.get(index) {
+                Some(_) => millis_arg(&args, index, "fromDate"),
+                None => default,
+            };
+
+            let date = DateTime {
+                year: part(0, 1970),
+                month: part(1, 1),
+                day: part(2, 1),
+                hour: part(3, 0),
+                minute: part(4, 0),
+                second: part(5, 0),
+                millis: part(6, 0),
+            };
+
+            if !date.is_valid() {
+                return RuntimeValue::Null;
+            }
+
+            RuntimeValue::Number(date.to_unix_millis() as f64)
+        }),
+    ]
+}
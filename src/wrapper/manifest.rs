@@ -0,0 +1,158 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// A `mosa.toml` project manifest.
+///
+/// ```toml
+/// [project]
+/// name = "game"
+/// entry = "src/main.mosa"
+/// roots = ["scripts"]
+///
+/// [libs]
+/// std = "./lib/std"
+///
+/// [natives]
+/// allow = ["mosa-native~>printLn", "mosa-native~>*"]
+///
+/// [dependencies]
+/// vectors = "1.2"
+/// shared = { path = "../shared" }
+///
+/// [registry]
+/// path = "../registry"
+/// ```
+/// Packages have a `[package]` table instead of, or next to `[project]`.
+/// Paths are relative to the directory of the manifest.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Manifest {
+    pub project: Option<ProjectSection>,
+    pub package: Option<PackageSection>,
+    #[serde(default)]
+    pub libs: HashMap<String, PathBuf>,
+    pub natives: Option<NativesSection>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+    pub registry: Option<RegistrySection>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProjectSection {
+    pub name: String,
+    pub entry: PathBuf,
+    /// extra directories searched for modules, after the directory of the entry
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PackageSection {
+    pub name: String,
+    pub version: String,
+    /// the directory `use @<name>:...` reads modules from
+    #[serde(default = "default_package_lib")]
+    pub lib: PathBuf,
+}
+
+fn default_package_lib() -> PathBuf {
+    PathBuf::from(".")
+}
+
+/// `name = "1.2"` or `name = { version = "1.2", path = "../name" }`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+    Version(String),
+    Detailed {
+        version: Option<String>,
+        path: Option<PathBuf>,
+    },
+}
+
+impl Dependency {
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            Dependency::Version(v) => Some(v),
+            Dependency::Detailed { version, .. } => version.as_deref(),
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Dependency::Version(_) => None,
+            Dependency::Detailed { path, .. } => path.as_deref(),
+        }
+    }
+}
+
+/// A directory of `<name>-<version>.tar` package archives.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RegistrySection {
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NativesSection {
+    /// paths of the native functions the scripts may use, a trailing `*` matches any suffix
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(source)?)
+    }
+}
+
+impl Manifest {
+    /// Reads the manifest, and makes all of its paths relative to the current directory.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read manifest `{}`: {}", path.display(), e))?;
+
+        let mut manifest = source
+            .parse::<Self>()
+            .map_err(|e| anyhow::anyhow!("Invalid manifest `{}`: {}", path.display(), e))?;
+
+        let base = path.parent().unwrap_or(Path::new("."));
+
+        if let Some(project) = &mut manifest.project {
+            project.entry = base.join(&project.entry);
+            project.roots = project.roots.iter().map(|r| base.join(r)).collect();
+        }
+
+        if let Some(package) = &mut manifest.package {
+            package.lib = base.join(&package.lib);
+        }
+
+        if let Some(registry) = &mut manifest.registry {
+            registry.path = base.join(&registry.path);
+        }
+
+        for dir in manifest.libs.values_mut() {
+            *dir = base.join(&*dir);
+        }
+
+        for dependency in manifest.dependencies.values_mut() {
+            if let Dependency::Detailed { path: Some(path), .. } = dependency {
+                *path = base.join(&*path);
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+/// Checks a native function path against capability patterns like `mosa-native~>*`.
+pub fn is_native_allowed(path: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => path.starts_with(prefix),
+        None => path == pattern,
+    })
+}
@@ -1,5 +1,6 @@
 pub mod runner;
 pub mod bindings;
+pub mod manifest;
 
 pub use bindings::MoSaBinding;
 pub use runner::MoSaRunner;
\ No newline at end of file
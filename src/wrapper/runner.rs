@@ -1,14 +1,21 @@
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::exit;
 use std::sync::{Arc, RwLock};
+use crate::err;
 use crate::global::{DataType, PrimitiveDataType};
 use crate::interpreter::Interpreter;
 use crate::interpreter::scope::{RuntimeScope, RuntimeScopeW};
 use crate::interpreter::structs::{MoSaNativeFunction, RuntimeValue};
+use crate::modules::resolver::{mosa_path, FileSystemResolver, ModuleRequest, ModuleResolver};
 use crate::modules::{Module, ModuleStorage};
+use crate::packages;
 use crate::parser::Parser;
+use crate::stdlib;
+use crate::stdlib::time::Clock;
+use crate::stdlib::{StdOptions, StdResolver};
+use crate::wrapper::manifest::{is_native_allowed, Manifest};
 use crate::wrapper::MoSaBinding;
 
 #[derive(Clone)]
@@ -16,7 +23,14 @@ pub struct MoSaRunner {
     entry: PathBuf,
     bindings: Vec<MoSaBinding>,
     scope_operations: Vec<Arc<dyn Fn(RuntimeScopeW)>>,
-    libs: HashMap<String, PathBuf>
+    libs: HashMap<String, PathBuf>,
+    /// replaces the file system, `entry` is then a module path like `scripts:main`
+    resolver: Option<Arc<dyn ModuleResolver>>,
+    /// searched for modules after the directory of the entry, before `MOSA_PATH`
+    search_paths: Vec<PathBuf>,
+    /// native function paths the scripts may call, `None` allows all bindings
+    capabilities: Option<Vec<String>>,
+    std_options: StdOptions,
 }
 
 impl MoSaRunner {
@@ -25,7 +39,42 @@ impl MoSaRunner {
             entry: entry.into(),
             bindings: vec![],
             scope_operations: vec![],
-            libs: HashMap::new()
+            libs: HashMap::new(),
+            resolver: None,
+            search_paths: vec![],
+            capabilities: None,
+            std_options: StdOptions::default(),
+        }
+    }
+
+    /// Creates a runner from a `mosa.toml` project manifest.
+    pub fn from_manifest(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let manifest = Manifest::load(path)?;
+
+        let Some(project) = manifest.project.clone() else {
+            anyhow::bail!("Manifest `{}` has no [project] table.", path.display());
+        };
+
+        let mut runner = Self::new(project.entry);
+
+        for package in packages::install(path, &manifest)? {
+            runner.libs.insert(package.name, package.lib);
+        }
+
+        // libraries declared by hand take priority over packages with the same name
+        runner.libs.extend(manifest.libs);
+        runner.search_paths = project.roots;
+        runner.capabilities = manifest.natives.map(|n| n.allow);
+
+        Ok(runner)
+    }
+
+    /// Loads all modules, including the entry, through the resolver.
+    pub fn with_resolver(&self, resolver: impl ModuleResolver + 'static) -> Self {
+        Self {
+            resolver: Some(Arc::new(resolver)),
+            ..self.clone()
         }
     }
 
@@ -50,34 +99,157 @@ impl MoSaRunner {
         }
     }
 
+    pub fn add_search_path(&self, path: impl Into<PathBuf>) -> Self {
+        let mut sp = self.search_paths.clone();
+
+        sp.push(path.into());
+
+        Self {
+            search_paths: sp,
+            ..self.clone()
+        }
+    }
+
+    /// Only lets scripts call the native functions matching `allowed`, e.g. `mosa-native~>*`.
+    pub fn allow_natives(&self, allowed: Vec<String>) -> Self {
+        Self {
+            capabilities: Some(allowed),
+            ..self.clone()
+        }
+    }
+
+    /// Lets `std:fs` access `root` and everything inside of it.
+    pub fn grant_fs(&self, root: impl Into<PathBuf>) -> Self {
+        let mut options = self.std_options.clone();
+
+        options.fs_roots.push(root.into());
+
+        Self {
+            std_options: options,
+            ..self.clone()
+        }
+    }
+
+    /// Replaces the clock of `std:time`, e.g. with a `FakeClock`.
+    pub fn with_clock(&self, clock: impl Clock + 'static) -> Self {
+        Self {
+            std_options: StdOptions {
+                clock: Arc::new(clock),
+                ..self.std_options.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Makes `std:random` give the same numbers on every run.
+    pub fn with_random_seed(&self, seed: u64) -> Self {
+        Self {
+            std_options: StdOptions {
+                random_seed: Some(seed),
+                ..self.std_options.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Lets scripts read and change environment variables and the current directory with `std:env`.
+    pub fn grant_env(&self) -> Self {
+        Self {
+            std_options: StdOptions {
+                env: true,
+                ..self.std_options.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// The arguments `args` of `std:env` returns.
+    pub fn with_args(&self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            std_options: StdOptions {
+                args: args.into_iter().map(Into::into).collect(),
+                ..self.std_options.clone()
+            },
+            ..self.clone()
+        }
+    }
+
+    /// Lets scripts run other programs and exit with `std:process`.
+    pub fn grant_process(&self) -> Self {
+        Self {
+            std_options: StdOptions {
+                process: true,
+                ..self.std_options.clone()
+            },
+            ..self.clone()
+        }
+    }
+
     pub fn run(&self) -> anyhow::Result<RuntimeValue> {
         let mut rs = RuntimeScope::new(None);
 
         rs.declare_variable("null".to_string(), DataType::Primitive(PrimitiveDataType::Null), RuntimeValue::Null, true);
 
-        for binding in self.bindings.clone() {
+        for binding in stdlib::bindings(&self.std_options) {
             rs.add_native_function(binding.path, binding.binding)
         }
 
-        let read = fs::read_to_string(&self.entry)?;
+        for binding in self.bindings.clone() {
+            match &self.capabilities {
+                Some(allowed) if !is_native_allowed(&binding.path, allowed) => {
+                    let path = binding.path.clone();
+
+                    rs.add_native_function(binding.path, Arc::new(move |_| {
+                        err!(intrp "Native function `{}` is not allowed by the project manifest.", path);
+                        exit(100)
+                    }))
+                }
+                _ => rs.add_native_function(binding.path, binding.binding),
+            }
+        }
+
+        let (resolver, r, path): (Arc<dyn ModuleResolver>, String, String) = match &self.resolver {
+            Some(resolver) => (
+                resolver.clone(),
+                String::new(),
+                self.entry.to_string_lossy().trim_end_matches(".mosa").replace(':', "/"),
+            ),
+            None => {
+                let mut root = self.entry.clone();
 
-        let mut root = self.entry.clone();
+                root.pop();
 
-        root.pop();
+                let mut r = root.into_os_string().to_str().unwrap().to_string();
 
-        let mut r = root.into_os_string().to_str().unwrap().to_string();
+                if !r.ends_with('/') {
+                    r.push('/');
+                }
 
-        if !r.ends_with('/') {
-            r.push('/');
-        }
+                let fname = self.entry.as_path().file_name().unwrap().to_str().unwrap().to_string();
+
+                let resolver = FileSystemResolver::new(r.clone())
+                    .add_libs(self.libs.clone())
+                    .add_search_paths(self.search_paths.clone())
+                    .add_search_paths(mosa_path());
+
+                (Arc::new(resolver), r, fname.strip_suffix(".mosa").unwrap().to_string())
+            }
+        };
+
+        let resolver: Arc<dyn ModuleResolver> = Arc::new(StdResolver::new(resolver));
+
+        let resolved = resolver.resolve(&ModuleRequest { lib: None, path: path.clone() })?;
 
-        let fname = self.entry.as_path().file_name().unwrap().to_str().unwrap().to_string();
+        let relative_root = match path.rsplit_once('/') {
+            Some((parent, _)) => format!("{}/", parent),
+            None => String::new(),
+        };
 
-        let module = Module::new(fname.strip_suffix(".mosa").unwrap().to_string());
+        let module = Module::new(path, resolved.id);
 
         let module_storage = Arc::new(ModuleStorage::new());
 
-        let mut parser = Parser::new(read, module, module_storage.clone(), r, "".to_string(), self.libs.clone());
+        let mut parser = Parser::new(resolved.source, module, module_storage.clone(), r, relative_root, resolver);
 
         let interpreter = Interpreter::new(parser.gen_ast(), module_storage.clone());
 
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+
+const COUNTER: &str = r#"
+class Counter (start: num) {
+    self.count = start;
+} {
+    let count: num;
+    pri let step: num = 1;
+
+    tied fn inc() -> null {
+        self.count = self.count + self.step;
+    }
+}
+"#;
+
+#[test]
+fn private_fields_are_not_printed() {
+    let res = run_main(&format!(
+        r#"{}
+        let c = Counter->new(10);
+        c.inc();
+        c"#,
+        COUNTER
+    ));
+
+    assert_eq!(res.to_string(), "Counter { count = 11 }");
+}
+
+#[test]
+fn private_fields_are_not_converted_to_json() {
+    let res = run_main(&format!(
+        r#"use @std:json as json;
+        {}
+        json::stringify(Counter->new(1))"#,
+        COUNTER
+    ));
+
+    assert_eq!(res, string(r#"{"count":1}"#));
+}
+
+#[test]
+fn destructors_run_from_the_child_to_the_parent() {
+    let res = run_main(
+        r#"let log: str = "";
+        class Base () { } {
+            let id: num = 0;
+        } () {
+            log = log + "base;";
+        }
+        class Child < Base () {
+            super();
+        } { } () {
+            log = log + "child;";
+        }
+        fn make() -> null {
+            let c = Child->new();
+        }
+        make();
+        log"#,
+    );
+
+    assert_eq!(res, string("child;base;"));
+}
+
+#[test]
+fn subclasses_override_and_call_super() {
+    let res = run_main(&format!(
+        r#"{}
+        class Loud < Counter (start: num) {{
+            super(start * 10);
+        }} {{
+            tied fn inc() -> null {{
+                super.inc();
+                super.inc();
+            }}
+        }}
+        fn bump(c: Counter) -> num {{
+            c.inc();
+            c.count
+        }}
+        bump(Loud->new(1))"#,
+        COUNTER
+    ));
+
+    assert_eq!(res, num(12.0));
+}
+
+#[test]
+fn private_fields_cannot_be_accessed_outside_of_the_class() {
+    let message = error_of(COUNTER, "Counter->new(1).step");
+
+    assert!(message.contains("is private in class `Counter`"), "{}", message);
+}
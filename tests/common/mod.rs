@@ -0,0 +1,93 @@
+#![allow(dead_code)]
+
+use mountain_sakura::prelude::*;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs the module `main` with the other modules served from memory.
+pub fn run(modules: &[(&str, &str)]) -> RuntimeValue {
+    let resolver = modules
+        .iter()
+        .fold(MemoryResolver::new(), |resolver, (path, source)| {
+            resolver.add_module(*path, *source)
+        });
+
+    MoSaRunner::new("main")
+        .with_resolver(resolver)
+        .run()
+        .unwrap()
+}
+
+/// Runs `script` in a child process, for errors that stop the script instead of being thrown,
+/// and returns what it printed. `test` is the name of the calling test, the child runs only that one.
+pub fn run_stopping(test: &str, script: impl FnOnce()) -> String {
+    if env::var_os("MOSA_TEST_CHILD").is_some() {
+        script();
+        std::process::exit(0)
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env("MOSA_TEST_CHILD", "1")
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(100),
+        "the script did not stop with an error"
+    );
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Runs a script that has no imports other than `@std`.
+pub fn run_main(source: &str) -> RuntimeValue {
+    run(&[("main", source)])
+}
+
+/// The message of the error thrown by `expr`, runtime errors are caught as `Error`s.
+pub fn error_of(setup: &str, expr: &str) -> String {
+    let source = format!(
+        r#"{}
+        let message: str = "no error";
+        try {{ {}; }} catch (e) {{ message = e.message; }}
+        message"#,
+        setup, expr
+    );
+
+    match run_main(&source) {
+        RuntimeValue::String(message) => message,
+        v => panic!("expected a message, got `{}`", v),
+    }
+}
+
+pub fn num(v: f64) -> RuntimeValue {
+    RuntimeValue::Number(v)
+}
+
+pub fn string(v: &str) -> RuntimeValue {
+    RuntimeValue::String(v.to_string())
+}
+
+/// An empty directory for a test, named after it so tests running at once don't share one.
+pub fn temp_dir(test: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("mosa-{}-{}", test, std::process::id()));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Writes `files`, given as paths relative to `dir` and their contents.
+pub fn write_files(dir: &Path, files: &[(&str, &str)]) {
+    for (path, content) in files {
+        let path = dir.join(path);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
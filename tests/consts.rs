@@ -0,0 +1,63 @@
+mod common;
+
+use common::{num, run_main};
+use mountain_sakura::parser::consts::fold_const;
+use mountain_sakura::prelude::RuntimeValue;
+use mountain_sakura::parser::structs::{ASTNode, BinaryExpression, ExpressionType, Operand};
+
+fn binary(left: ASTNode, operand: Operand, right: ASTNode) -> ASTNode {
+    ASTNode::Expression(ExpressionType::Binary(Box::new(BinaryExpression {
+        left: Box::new(left),
+        right: Box::new(right),
+        operand,
+    })))
+}
+
+fn entry(name: &str) -> ASTNode {
+    ASTNode::ComplexTypeAccessor("Color".to_string(), name.to_string())
+}
+
+#[test]
+fn folds_arithmetic_and_comparisons() {
+    let sum = binary(ASTNode::Number(2.0), Operand::Plus, ASTNode::Number(3.0));
+
+    assert_eq!(fold_const(&sum), Ok(ASTNode::Number(5.0)));
+    assert_eq!(
+        fold_const(&binary(sum, Operand::SmallerEqual, ASTNode::Number(5.0))),
+        Ok(ASTNode::Boolean(true))
+    );
+    assert_eq!(
+        fold_const(&binary(entry("Red"), Operand::Equality, entry("Red"))),
+        Ok(ASTNode::Boolean(true))
+    );
+}
+
+#[test]
+fn mixed_operands_report_their_kinds() {
+    let res = fold_const(&binary(entry("Red"), Operand::Equality, ASTNode::Number(1.0)));
+
+    assert_eq!(res, Err("an enum entry cannot be combined with a number".to_string()));
+
+    let res = fold_const(&binary(ASTNode::Boolean(true), Operand::Plus, entry("Red")));
+
+    assert_eq!(res, Err("a boolean cannot be combined with an enum entry".to_string()));
+}
+
+#[test]
+fn constants_are_usable_in_later_constants() {
+    let res = run_main(
+        r#"enum Mode { Fast, Slow }
+        const SIZE = 2 * 3;
+        const TOTAL: num = SIZE + 1;
+        const FAST = Mode->Fast == Mode->Fast;
+        TOTAL"#,
+    );
+
+    assert_eq!(res, num(7.0));
+}
+
+#[test]
+fn smaller_or_equal_is_true_for_equal_values() {
+    assert_eq!(run_main("const A = 2 <= 2; A"), RuntimeValue::Bool(true));
+    assert_eq!(run_main("let a = 2; 2 <= a"), RuntimeValue::Bool(true));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{error_of, run_main};
+use mountain_sakura::prelude::*;
+
+const NODE: &str = r#"
+layout Node {
+    value: num = 0,
+    next: nul Node = null
+} derive [Eq, Clone]
+"#;
+
+#[test]
+fn clone_copies_nested_layouts() {
+    let res = run_main(&format!(
+        r#"{}
+        let a = Node {{ next = Node {{ value = 1 }} }};
+        let b = a.clone();
+        b.next.value = 2;
+        a.next.value * 10 + b.next.value"#,
+        NODE
+    ));
+
+    assert_eq!(res, RuntimeValue::Number(12.0));
+}
+
+#[test]
+fn eq_and_clone_handle_cycles() {
+    let run = |expr: &str| {
+        run_main(&format!(
+            r#"{}
+            let a = Node {{ value = 1 }};
+            a.next = a;
+            let b = a.clone();
+            b.value = 2;
+            {}"#,
+            NODE, expr
+        ))
+    };
+
+    assert_eq!(run("a.eq(a.clone())"), RuntimeValue::Bool(true));
+    assert_eq!(run("a.eq(b)"), RuntimeValue::Bool(false));
+    // the copy refers to itself, not to the original
+    assert_eq!(run("b.next.value"), RuntimeValue::Number(2.0));
+}
+
+#[test]
+fn clone_shares_class_instances() {
+    let res = run_main(&format!(
+        r#"class Counter () {{ }} {{
+            let count: num = 0;
+        }}
+        layout Holder {{
+            counter: nul Counter = null
+        }} derive [Clone]
+        {}
+        let a = Holder {{ counter = Counter->new() }};
+        let b = a.clone();
+        b.counter.count = 5;
+        a.counter.count"#,
+        NODE
+    ));
+
+    assert_eq!(res, RuntimeValue::Number(5.0));
+}
+
+const POINT: &str = r#"
+enum Color { Red, Blue }
+
+layout Point {
+    x: num = 0,
+    y: num = 0,
+    color: Color = Color->Red
+} derive [Default, Display, Json]
+"#;
+
+#[test]
+fn default_and_display_use_the_field_defaults() {
+    let res = run_main(&format!("{}\nPoint->default().toStr()", POINT));
+
+    assert_eq!(res, RuntimeValue::String("Point { x = 0, y = 0, color = Color->Red }".to_string()));
+}
+
+#[test]
+fn json_round_trips_and_fills_missing_fields() {
+    let res = run_main(&format!(
+        r#"{}
+        let p = Point->fromJson("{{\"x\": 2, \"color\": \"Blue\"}}");
+        p.toJson()"#,
+        POINT
+    ));
+
+    assert_eq!(res, RuntimeValue::String(r#"{"x":2,"y":0,"color":"Blue"}"#.to_string()));
+}
+
+#[test]
+fn from_json_rejects_unknown_enum_entries() {
+    let message = error_of(POINT, r#"Point->fromJson("{\"color\": \"Green\"}")"#);
+
+    assert!(message.contains("Expected an entry of enum `Color`"), "{}", message);
+}
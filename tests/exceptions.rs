@@ -0,0 +1,105 @@
+mod common;
+
+use common::{error_of, run, run_main, run_stopping};
+use mountain_sakura::prelude::*;
+
+#[test]
+fn module_is_usable_after_a_caught_assignment_error() {
+    let res = run(&[
+        (
+            "modp",
+            r#"let n: num = 1;
+            exp fn setN() -> null { n = "s"; }
+            exp fn getN() -> num { n }"#,
+        ),
+        (
+            "main",
+            r#"use modp as m;
+            try { m::setN(); } catch { }
+            m::getN()"#,
+        ),
+    ]);
+
+    assert_eq!(res, RuntimeValue::Number(1.0));
+}
+
+#[test]
+fn natives_are_usable_after_a_caught_error() {
+    let res = run(&[(
+        "main",
+        r#"use @std:random as r;
+        use @std:regex as re;
+        try { r::integer(3, 2); } catch { }
+        try { re::isMatch("(", "x"); } catch { }
+        try { re::compile("("); } catch { }
+        re::isMatch("a", "a") == (r::integer(1, 1) == 1)"#,
+    )]);
+
+    assert_eq!(res, RuntimeValue::Bool(true));
+}
+
+#[test]
+fn runtime_errors_are_caught_with_their_message() {
+    let message = error_of("use @std:math as m;", "m::idiv(1, 0)");
+
+    assert_eq!(message, "Integer division by zero.");
+}
+
+#[test]
+fn thrown_strings_become_errors_with_a_stack() {
+    let res = run_main(
+        r#"use @std:str as s;
+        fn parseAge(text: str) -> num {
+            if text == "" {
+                throw "the age is missing";
+            }
+            42
+        }
+        try { parseAge(""); "not thrown" } catch (e) { e.message + " in " + s::join(e.stack, ",") }"#,
+    );
+
+    assert_eq!(
+        res,
+        RuntimeValue::String("the age is missing in parseAge".to_string())
+    );
+}
+
+#[test]
+fn try_gives_the_value_of_the_block_that_ran_last() {
+    let res = run_main(
+        r#"let fallback = try { throw "no"; 1 } catch { 0 };
+        let value = try { 5 } catch { 0 };
+        fallback + value"#,
+    );
+
+    assert_eq!(res, RuntimeValue::Number(5.0));
+}
+
+#[test]
+fn values_are_thrown_again_after_finally_without_catch() {
+    let res = run_main(
+        r#"layout ValidationError { field: str, reason: str }
+        let log = "";
+        try {
+            try {
+                throw ValidationError { field = "age", reason = "negative" };
+            } finally {
+                log = log + "finally ";
+            }
+        } catch (e) {
+            log = log + e.field;
+        }
+        log"#,
+    );
+
+    assert_eq!(res, RuntimeValue::String("finally age".to_string()));
+}
+
+#[test]
+fn uncaught_errors_stop_the_script() {
+    let output = run_stopping("uncaught_errors_stop_the_script", || {
+        run(&[("main", "fn f() -> null { throw \"broken\"; }\nf()")]);
+    });
+
+    assert!(output.contains("Uncaught `Error`: broken"), "{}", output);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{num, run, run_main, run_stopping};
+
+const FIBONACCI: &str = r#"use @std:iter as it;
+gen fn fibonacci() -> num {
+    let a = 0;
+    let b = 1;
+
+    for 0..1/0 {
+        yield a;
+        let next = a + b;
+        a = b;
+        b = next;
+    }
+}
+"#;
+
+#[test]
+fn endless_generators_are_pulled_lazily() {
+    let res = run_main(&format!("{}fibonacci() ->> it::take(8)", FIBONACCI));
+
+    assert_eq!(res.to_string(), "[0, 1, 1, 2, 3, 5, 8, 13]");
+}
+
+#[test]
+fn the_body_only_runs_when_a_value_is_asked_for() {
+    let res = run_main(
+        r#"let runs = 0;
+        gen fn counted() -> num {
+            runs = runs + 1;
+            yield 1;
+        }
+        let g = counted();
+        let before = runs;
+        for g { }
+        before * 10 + runs"#,
+    );
+
+    assert_eq!(res, num(1.0));
+}
+
+#[test]
+fn generators_are_used_up_once() {
+    let res = run_main(
+        r#"gen fn three() -> num { yield 1; yield 2; yield 3; }
+        let g = three();
+        let sum = 0;
+        for g { sum = sum + ^value; }
+        for g { sum = sum + ^value; }
+        for three() { sum = sum + ^value * 10; }
+        sum"#,
+    );
+
+    assert_eq!(res, num(66.0));
+}
+
+#[test]
+fn tied_gen_fn_iter_makes_a_layout_iterable() {
+    let res = run_main(
+        r#"layout Pair {
+            first: num,
+            second: num
+        } mix @ {
+            tied gen fn iter() -> num {
+                yield self.first;
+                yield self.second;
+            }
+        }
+        let sum = 0;
+        for (Pair { first = 4, second = 5 }) { sum = sum * 10 + ^value; }
+        sum"#,
+    );
+
+    assert_eq!(res, num(45.0));
+}
+
+#[test]
+fn yield_outside_of_a_gen_fn_is_an_error() {
+    let output = run_stopping("yield_outside_of_a_gen_fn_is_an_error", || {
+        run(&[("main", "yield 1;\n\"unreachable\"")]);
+    });
+
+    assert!(
+        output.contains("`yield` can only be used in the body of a `gen fn`."),
+        "{}",
+        output
+    );
+}
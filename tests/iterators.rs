@@ -0,0 +1,87 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+use mountain_sakura::prelude::*;
+
+const LIST: &str = r#"layout Node {
+    value: num,
+    rest: nul Node
+}
+
+layout ListIter {
+    current: nul Node
+} mix @ {
+    tied fn next() -> nul num {
+        if self.current == null {
+            null
+        } else {
+            let v = self.current.value;
+            self.current = self.current.rest;
+            v
+        }
+    }
+}
+
+layout List {
+    head: nul Node
+} mix @ {
+    tied fn iter() -> ListIter {
+        ListIter { current = self.head }
+    }
+}
+
+let list = List { head = Node { value = 1, rest = Node { value = 2, rest = Node { value = 3, rest = null } } } };
+"#;
+
+#[test]
+fn layouts_with_a_tied_iter_are_looped_over() {
+    let res = run_main(&format!(
+        r#"{}
+        let res = 0;
+        for list {{ res = res * 10 + ^value; }}
+        for list {{ res = res * 10 + ^index; }}
+        res"#,
+        LIST
+    ));
+
+    assert_eq!(res, num(123012.0));
+}
+
+#[test]
+fn iterators_work_with_std_iter() {
+    let res = run_main(&format!(
+        r#"use @std:iter as it;
+        {}
+        list ->> it::map(::(x: num) -> num {{ x * 2 }}) ->> it::sum"#,
+        LIST
+    ));
+
+    assert_eq!(res, num(12.0));
+}
+
+#[test]
+fn strings_are_looped_over_by_char() {
+    let res = run_main(
+        r#"let res = "";
+        for "héllo" { res = ^value + res; }
+        res"#,
+    );
+
+    assert_eq!(res, string("olléh"));
+}
+
+#[test]
+fn null_is_equal_to_null() {
+    assert_eq!(run_main("null == null"), RuntimeValue::Bool(true));
+    assert_eq!(run_main("null != null"), RuntimeValue::Bool(false));
+}
+
+#[test]
+fn layouts_without_next_or_iter_are_not_iterable() {
+    let message = error_of("layout Empty { }", "for (Empty { }) { }");
+
+    assert_eq!(
+        message,
+        "Cannot iterate over a value of type `Empty`, layouts need a tied `iter` or `next` function."
+    );
+}
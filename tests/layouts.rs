@@ -0,0 +1,103 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+
+const LAYOUTS: &str = r#"
+layout Named {
+    name: str = "unnamed"
+}
+
+layout User {
+    ..Named,
+    age: num = 1
+}
+
+layout Admin {
+    ..User
+}
+
+fn nameOf(n: Named) -> str { n.name }
+"#;
+
+#[test]
+fn embedding_layouts_match_the_embedded_type() {
+    let res = run_main(&format!(
+        r#"{}
+        nameOf(User {{ name = "sakura" }}) + nameOf(Admin {{ }})"#,
+        LAYOUTS
+    ));
+
+    assert_eq!(res, string("sakuraunnamed"));
+}
+
+#[test]
+fn typeof_gives_the_layout_itself() {
+    let res = run_main(&format!("{}\ntypeof User {{ }}", LAYOUTS));
+
+    assert_eq!(res, string("User"));
+}
+
+#[test]
+fn embedded_layouts_do_not_match_the_embedding_type() {
+    let message = error_of(
+        &format!("{}\nfn ageOf(u: User) -> num {{ u.age }}", LAYOUTS),
+        "ageOf(Named {})",
+    );
+
+    assert!(message.contains("Named"), "{}", message);
+}
+
+const CAR: &str = r#"
+layout Engine {
+    power: num = 100
+} mix @ {
+    tied fn start() -> str {
+        "vroom"
+    }
+}
+
+layout Car {
+    engine: Engine,
+    power: num = 1
+}
+
+layout Bike {
+    engine: Engine
+}
+
+expand Car {
+    $via engine;
+}
+
+expand Bike {
+    $via engine;
+}
+"#;
+
+#[test]
+fn expand_forwards_fields_and_tied_functions() {
+    let run = |expr: &str| {
+        run_main(&format!(
+            r#"{}
+            let b = Bike {{ engine = Engine {{ }} }};
+            b.power = 120;
+            {}"#,
+            CAR, expr
+        ))
+    };
+
+    assert_eq!(run("b.start()"), string("vroom"));
+    assert_eq!(run("b.engine.power"), num(120.0));
+}
+
+#[test]
+fn own_fields_are_found_before_expanded_ones() {
+    let res = run_main(&format!(
+        r#"{}
+        let c = Car {{ engine = Engine {{ }} }};
+        c.power + c.engine.power"#,
+        CAR
+    ));
+
+    assert_eq!(res, num(101.0));
+}
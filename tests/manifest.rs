@@ -0,0 +1,83 @@
+mod common;
+
+use common::{num, run_stopping, temp_dir, write_files};
+use mountain_sakura::prelude::*;
+
+#[test]
+fn manifest_sets_the_entry_roots_and_libs() {
+    let dir = temp_dir("manifest_sets_the_entry_roots_and_libs");
+
+    write_files(
+        &dir,
+        &[
+            (
+                "mosa.toml",
+                r#"[project]
+                name = "game"
+                entry = "src/main.mosa"
+                roots = ["shared"]
+
+                [libs]
+                util = "./lib/util""#,
+            ),
+            ("src/main.mosa", "use helper~>two;\nuse @util:three~>three;\ntwo() * three()"),
+            ("shared/helper.mosa", "exp fn two() -> num { 2 }"),
+            ("lib/util/three.mosa", "exp fn three() -> num { 3 }"),
+        ],
+    );
+
+    let res = MoSaRunner::from_manifest(dir.join("mosa.toml")).unwrap().run().unwrap();
+
+    assert_eq!(res, num(6.0));
+}
+
+#[test]
+fn natives_outside_of_the_allow_list_stop_the_script() {
+    let dir = temp_dir("natives_outside_of_the_allow_list_stop_the_script");
+
+    write_files(
+        &dir,
+        &[
+            (
+                "mosa.toml",
+                r#"[project]
+                name = "game"
+                entry = "main.mosa"
+
+                [natives]
+                allow = ["mosa-native~>print*"]"#,
+            ),
+            (
+                "main.mosa",
+                r#"use native fn printLn#"mosa-native~>printLn";
+                use native fn exit#"mosa-native~>exit";
+                printLn("allowed");
+                exit()"#,
+            ),
+        ],
+    );
+
+    let binding = |name: &str| {
+        (|_: Vec<RuntimeValue>| -> RuntimeValue { RuntimeValue::Null })
+            .binding(format!("mosa-native~>{}", name))
+    };
+
+    let output = run_stopping("natives_outside_of_the_allow_list_stop_the_script", || {
+        let runner = MoSaRunner::from_manifest(dir.join("mosa.toml"))
+            .unwrap()
+            .add_bindings(vec![binding("printLn"), binding("exit")]);
+
+        runner.run().unwrap();
+    });
+
+    assert!(output.contains("`mosa-native~>exit` is not allowed by the project manifest"), "{}", output);
+}
+
+#[test]
+fn broken_manifests_are_reported() {
+    let dir = temp_dir("broken_manifests_are_reported");
+
+    write_files(&dir, &[("mosa.toml", "[project]\nentry = 1")]);
+
+    assert!(MoSaRunner::from_manifest(dir.join("mosa.toml")).is_err());
+}
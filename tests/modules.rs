@@ -0,0 +1,114 @@
+mod common;
+
+use common::{num, run, run_stopping, string};
+
+const OTHER: (&str, &str) = (
+    "other",
+    r#"exp enum Mode { Fast, Slow }
+    exp immut let greeting = "hello";
+    exp const LIMIT = 100;
+    exp fn hi() -> str { "hi" }
+    10"#,
+);
+
+#[test]
+fn enums_immutable_variables_and_constants_are_exported() {
+    let main = |expr: &str| {
+        let source = format!(
+            r#"use other~>Mode;
+            use other~>greeting;
+            use other~>LIMIT;
+            {}"#,
+            expr
+        );
+
+        run(&[OTHER, ("main", &source)])
+    };
+
+    assert_eq!(main("typeof Mode->Slow"), string("Mode"));
+    assert_eq!(main("greeting"), string("hello"));
+    assert_eq!(main("1 + LIMIT"), num(101.0));
+}
+
+#[test]
+fn modules_re_export_their_imports() {
+    let res = run(&[
+        OTHER,
+        ("lib", "exp use other~>hi;\nexp use other~>LIMIT;"),
+        ("main", "use lib~>hi;\nhi()"),
+    ]);
+
+    assert_eq!(res, string("hi"));
+}
+
+const IMPORT: (&str, &str) = (
+    "test:import",
+    r#"exp fn hi() -> str { "hi" }
+    exp layout Hi { v: num = 1 }"#,
+);
+
+#[test]
+fn several_symbols_are_imported_with_aliases() {
+    let res = run(&[
+        IMPORT,
+        (
+            "main",
+            r#"use test:import~>[hi as hello, Hi];
+            let h = Hi { v = 2 };
+            hello()"#,
+        ),
+    ]);
+
+    assert_eq!(res, string("hi"));
+}
+
+#[test]
+fn wildcard_imports_everything_exported() {
+    let res = run(&[IMPORT, ("main", "use test:import~>*;\nlet h = Hi { };\nh.v")]);
+
+    assert_eq!(res, num(1.0));
+}
+
+#[test]
+fn namespaces_access_symbols_with_double_colons() {
+    let res = run(&[
+        IMPORT,
+        (
+            "main",
+            r#"use test:import as imp;
+            let h = imp::Hi { v = 3 };
+            h.v"#,
+        ),
+    ]);
+
+    assert_eq!(res, num(3.0));
+}
+
+#[test]
+fn modules_run_once_for_every_importer() {
+    let res = run(&[
+        ("counter", "let runs: num = 0;\nexp fn count() -> num { runs = runs + 1; runs }"),
+        ("a", "use counter~>count;\nexp fn a() -> num { count() }"),
+        ("b", "use counter~>count;\nexp fn b() -> num { count() }"),
+        ("main", "use a~>a;\nuse b~>b;\na();\nb()"),
+    ]);
+
+    assert_eq!(res, num(2.0));
+}
+
+#[test]
+fn import_cycles_show_the_chain() {
+    let output = run_stopping("import_cycles_show_the_chain", || {
+        run(&[
+            ("a", "use b~>b;\nexp fn a() -> num { 1 }"),
+            ("b", "use a~>a;\nexp fn b() -> num { 2 }"),
+            ("main", "use a~>a;\na()"),
+        ]);
+    });
+
+    assert!(
+        output.contains("Import cycle detected: memory:main -> memory:a -> memory:b -> memory:a"),
+        "{}",
+        output
+    );
+}
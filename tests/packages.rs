@@ -0,0 +1,117 @@
+mod common;
+
+use common::{num, string, temp_dir, write_files};
+use mountain_sakura::packages::registry::Registry;
+use mountain_sakura::prelude::*;
+use std::fs;
+use std::path::Path;
+
+fn package(dir: &Path, name: &str, version: &str, value: &str) {
+    write_files(
+        dir,
+        &[
+            (
+                &format!("{}-{}/mosa.toml", name, version),
+                &format!("[package]\nname = \"{}\"\nversion = \"{}\"", name, version),
+            ),
+            (
+                &format!("{}-{}/lib.mosa", name, version),
+                &format!("exp fn value() -> str {{ \"{}\" }}", value),
+            ),
+        ],
+    );
+
+    Registry::new(dir.join("registry"))
+        .publish(dir.join(format!("{}-{}/mosa.toml", name, version)))
+        .unwrap();
+}
+
+fn project(dir: &Path, dependencies: &str) {
+    write_files(
+        dir,
+        &[
+            (
+                "game/mosa.toml",
+                &format!(
+                    "[project]\nname = \"game\"\nentry = \"main.mosa\"\n\n[dependencies]\n{}\n\n[registry]\npath = \"../registry\"",
+                    dependencies
+                ),
+            ),
+            ("game/main.mosa", "use @vectors:lib~>value;\nvalue()"),
+        ],
+    );
+}
+
+fn run_game(dir: &Path) -> anyhow::Result<RuntimeValue> {
+    MoSaRunner::from_manifest(dir.join("game/mosa.toml"))?.run()
+}
+
+#[test]
+fn path_dependencies_become_libraries() {
+    let dir = temp_dir("path_dependencies_become_libraries");
+
+    write_files(
+        &dir,
+        &[
+            ("shared/mosa.toml", "[package]\nname = \"shared\"\nversion = \"0.1.0\"\nlib = \"src\""),
+            ("shared/src/greet.mosa", "exp fn two() -> num { 2 }"),
+            (
+                "game/mosa.toml",
+                "[project]\nname = \"game\"\nentry = \"main.mosa\"\n\n[dependencies]\nshared = { path = \"../shared\" }",
+            ),
+            ("game/main.mosa", "use @shared:greet~>two;\ntwo()"),
+        ],
+    );
+
+    assert_eq!(run_game(&dir).unwrap(), num(2.0));
+}
+
+#[test]
+fn the_newest_matching_version_is_locked() {
+    let dir = temp_dir("the_newest_matching_version_is_locked");
+
+    package(&dir, "vectors", "1.0.0", "one");
+    package(&dir, "vectors", "1.2.0", "two");
+    package(&dir, "vectors", "2.0.0", "three");
+    project(&dir, "vectors = \"^1.0\"");
+
+    assert_eq!(run_game(&dir).unwrap(), string("two"));
+    assert!(fs::read_to_string(dir.join("game/mosa.lock")).unwrap().contains("1.2.0"));
+
+    // a newer version doesn't replace the locked one
+    package(&dir, "vectors", "1.3.0", "four");
+
+    assert_eq!(run_game(&dir).unwrap(), string("two"));
+}
+
+#[test]
+fn versions_that_match_nothing_are_an_error() {
+    let dir = temp_dir("versions_that_match_nothing_are_an_error");
+
+    package(&dir, "vectors", "1.0.0", "one");
+    project(&dir, "vectors = \"^3.0\"");
+
+    assert!(run_game(&dir).is_err());
+}
+
+#[test]
+fn the_lockfile_is_removed_without_dependencies() {
+    let dir = temp_dir("the_lockfile_is_removed_without_dependencies");
+
+    package(&dir, "vectors", "1.0.0", "one");
+    project(&dir, "vectors = \"1\"");
+    run_game(&dir).unwrap();
+
+    assert!(dir.join("game/mosa.lock").exists());
+
+    write_files(
+        &dir,
+        &[
+            ("game/mosa.toml", "[project]\nname = \"game\"\nentry = \"main.mosa\""),
+            ("game/main.mosa", "\"done\""),
+        ],
+    );
+    run_game(&dir).unwrap();
+
+    assert!(!dir.join("game/mosa.lock").exists());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{num, run_main};
+
+/// The values of `range`, ranges themselves are printed like they are written.
+fn values(range: &str) -> String {
+    run_main(&format!("use @std:iter as it;\nit::collect({})", range)).to_string()
+}
+
+#[test]
+fn ranges_include_the_end_with_an_equals_sign() {
+    assert_eq!(values("0..5"), "[0, 1, 2, 3, 4]");
+    assert_eq!(values("0..=5"), "[0, 1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn ranges_count_down_when_the_end_is_below_the_start() {
+    assert_eq!(values("5..0"), "[5, 4, 3, 2, 1]");
+    assert_eq!(values("5..=0 step 2"), "[5, 3, 1]");
+}
+
+#[test]
+fn steps_can_be_fractions() {
+    assert_eq!(values("0..10 step 3"), "[0, 3, 6, 9]");
+    assert_eq!(values("0..1 step 0.25"), "[0, 0.25, 0.5, 0.75]");
+}
+
+#[test]
+fn huge_ranges_are_not_calculated_up_front() {
+    assert_eq!(
+        values("0..1000000000 step 250000000"),
+        "[0, 250000000, 500000000, 750000000]"
+    );
+
+    assert_eq!(
+        run_main("(0..1000000000 step 5)").to_string(),
+        "0..1000000000 step 5"
+    );
+}
+
+#[test]
+fn das_passes_the_left_value_as_the_first_argument() {
+    let res = run_main(
+        r#"fn double(x: num) -> num { x * 2 }
+        fn add(x: num, y: num) -> num { x + y }
+        3 ->> double ->> add(1)"#,
+    );
+
+    assert_eq!(res, num(7.0));
+}
+
+#[test]
+fn layouts_are_created_in_call_arguments_of_statement_heads() {
+    let res = run_main(
+        r#"layout Point { x: num = 1 }
+        fn getX(p: Point) -> num { p.x }
+        let res = 0;
+        if getX(Point { x = 2 }) == 2 { res = 1; }
+        for 0..getX(Point { }) { res = res + 10; }
+        res"#,
+    );
+
+    assert_eq!(res, num(11.0));
+}
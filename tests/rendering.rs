@@ -0,0 +1,47 @@
+mod common;
+
+use common::run_main;
+
+fn rendered(source: &str) -> String {
+    run_main(source).to_string()
+}
+
+#[test]
+fn layouts_render_fields_in_declaration_order() {
+    let res = rendered(
+        r#"enum Color { Red }
+        layout Inner { label: str = "hi" }
+        layout Outer {
+            b: num = 2,
+            a: Inner = Inner { },
+            color: Color = Color->Red
+        }
+        Outer { }"#,
+    );
+
+    assert_eq!(res, r#"Outer { b = 2, a = Inner { label = "hi" }, color = Color->Red }"#);
+}
+
+#[test]
+fn iterables_quote_their_strings() {
+    let res = rendered(
+        r#"use @std:str as s;
+        s::split("a,b", ",")"#,
+    );
+
+    assert_eq!(res, r#"["a", "b"]"#);
+}
+
+#[test]
+fn tied_to_str_replaces_the_default_rendering() {
+    let res = rendered(
+        r#"layout Money {
+            cents: num = 150
+        } mix @ {
+            tied fn toStr() -> str { "1.50$" }
+        }
+        Money { }"#,
+    );
+
+    assert_eq!(res, "1.50$");
+}
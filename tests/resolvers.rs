@@ -0,0 +1,56 @@
+mod common;
+
+use common::{num, string};
+use mountain_sakura::modules::resolver::{ModuleRequest, ResolveError, ResolvedModule};
+use mountain_sakura::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+static SCRIPTS: &[(&str, &str)] = &[
+    ("main", "use test:other~>twice;\ntwice()"),
+    ("test:other", "use import~>one;\nexp fn twice() -> num { one() * 2 }"),
+    ("test:import", "exp fn one() -> num { 1 }"),
+];
+
+#[test]
+fn static_resolver_resolves_relative_to_the_importing_module() {
+    let res = MoSaRunner::new("main")
+        .with_resolver(StaticResolver::new(SCRIPTS))
+        .run()
+        .unwrap();
+
+    assert_eq!(res, num(2.0));
+}
+
+/// Serves every module with the same source, and counts the lookups.
+struct CountingResolver {
+    lookups: Arc<AtomicUsize>,
+}
+
+impl ModuleResolver for CountingResolver {
+    fn resolve(&self, request: &ModuleRequest) -> Result<ResolvedModule, ResolveError> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+
+        let source = match request.path.as_str() {
+            "main" => "use asset~>name;\nname()",
+            _ => "exp fn name() -> str { \"from a pack\" }",
+        };
+
+        Ok(ResolvedModule {
+            id: format!("pack:{}", request.path),
+            source: source.to_string(),
+        })
+    }
+}
+
+#[test]
+fn custom_resolvers_provide_modules() {
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let res = MoSaRunner::new("main")
+        .with_resolver(CountingResolver { lookups: lookups.clone() })
+        .run()
+        .unwrap();
+
+    assert_eq!(res, string("from a pack"));
+    assert_eq!(lookups.load(Ordering::SeqCst), 2);
+}
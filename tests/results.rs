@@ -0,0 +1,88 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+use mountain_sakura::prelude::*;
+
+const AGES: &str = r#"use @std:result as r;
+fn parseAge(text: str) -> result[num, str] {
+    if text == "" {
+        Err("the age is missing")
+    } else {
+        Ok(42)
+    }
+}
+
+fn nextAge(text: str) -> result[num, str] {
+    let age = parseAge(text)?;
+    Ok(age + 1)
+}
+"#;
+
+fn ages(expr: &str) -> String {
+    run_main(&format!("{}{}", AGES, expr)).to_string()
+}
+
+#[test]
+fn question_mark_unwraps_an_ok() {
+    assert_eq!(ages(r#"nextAge("42")"#), "Ok(43)");
+}
+
+#[test]
+fn question_mark_returns_an_err_early() {
+    assert_eq!(ages(r#"nextAge("")"#), r#"Err("the age is missing")"#);
+}
+
+#[test]
+fn finally_runs_when_question_mark_returns() {
+    let res = run_main(&format!(
+        r#"{}
+        let log = "";
+        fn logged(text: str) -> result[num, str] {{
+            try {{
+                let age = parseAge(text)?;
+                Ok(age)
+            }} finally {{
+                log = log + "finally";
+            }}
+        }}
+        logged("");
+        log"#,
+        AGES
+    ));
+
+    assert_eq!(res, string("finally"));
+}
+
+#[test]
+fn results_are_used_with_std_result() {
+    assert_eq!(
+        run_main(&format!(r#"{}nextAge("") ->> r::unwrapOr(0)"#, AGES)),
+        num(0.0)
+    );
+    assert_eq!(
+        run_main(&format!(
+            r#"{}nextAge("1") ->> r::map(::(x: num) -> num {{ x * 2 }}) ->> r::unwrap"#,
+            AGES
+        )),
+        num(86.0)
+    );
+    assert_eq!(
+        run_main(&format!(r#"{}r::isErr(parseAge(""))"#, AGES)),
+        RuntimeValue::Bool(true)
+    );
+}
+
+#[test]
+fn question_mark_needs_a_result() {
+    let message = error_of("fn f() -> result[num, str] { let v = 5?; Ok(v) }", "f()");
+
+    assert_eq!(message, "`?` expects a result, got `5`.");
+}
+
+#[test]
+fn unwrapping_an_err_is_an_error() {
+    assert_eq!(
+        error_of(AGES, r#"r::unwrap(parseAge(""))"#),
+        r#"`unwrap` got `Err("the age is missing")`."#
+    );
+}
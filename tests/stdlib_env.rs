@@ -0,0 +1,69 @@
+mod common;
+
+use common::{run_main, string};
+use mountain_sakura::prelude::*;
+
+fn run_granted(source: &str) -> RuntimeValue {
+    let resolver = MemoryResolver::new().add_module("main", source);
+
+    MoSaRunner::new("main")
+        .with_resolver(resolver)
+        .grant_env()
+        .grant_process()
+        .with_args(["build", "--release"])
+        .run()
+        .unwrap()
+}
+
+#[test]
+fn env_and_process_are_denied_unless_granted() {
+    let res = run_main(
+        r#"use @std:env as env;
+        env::get("HOME").kind"#,
+    );
+    assert_eq!(res, string("permissionDenied"));
+
+    let res = run_main(
+        r#"use @std:process as process;
+        use @std:str as s;
+        process::run("echo", s::split("hi", " ")).kind"#,
+    );
+    assert_eq!(res, string("permissionDenied"));
+}
+
+#[test]
+fn args_are_the_ones_of_the_host() {
+    let res = run_granted(
+        r#"use @std:env as env;
+        use @std:str as s;
+        s::join(env::args(), " ")"#,
+    );
+
+    assert_eq!(res, string("build --release"));
+}
+
+#[test]
+fn variables_are_set_and_removed() {
+    let res = run_granted(
+        r#"use @std:env as env;
+        env::set("MOSA_TEST_VARIABLE", "sakura");
+        let set = env::get("MOSA_TEST_VARIABLE");
+        env::remove("MOSA_TEST_VARIABLE");
+        let removed = env::get("MOSA_TEST_VARIABLE");
+        if removed == null { set } else { "still set" }"#,
+    );
+
+    assert_eq!(res, string("sakura"));
+}
+
+#[test]
+fn programs_are_run_with_their_output_captured() {
+    let res = run_granted(
+        r#"use @std:process as process;
+        use @std:str as s;
+        let out = process::run("echo", s::split("hello sakura", " "));
+        if out.success { out.stdout } else { "failed" }"#,
+    );
+
+    assert_eq!(res, string("hello sakura\n"));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{string, temp_dir, write_files};
+use mountain_sakura::prelude::*;
+use std::fs;
+use std::path::Path;
+
+fn run_in(root: &Path, source: &str) -> RuntimeValue {
+    let resolver = MemoryResolver::new().add_module("main", format!("use @std:fs as fs;\n{}", source));
+
+    MoSaRunner::new("main")
+        .with_resolver(resolver)
+        .grant_fs(root)
+        .run()
+        .unwrap()
+}
+
+#[test]
+fn files_are_written_and_read_inside_of_the_sandbox() {
+    let dir = temp_dir("files_are_written_and_read_inside_of_the_sandbox");
+
+    let res = run_in(
+        &dir,
+        r#"fs::createDir("saves/slot");
+        fs::writeText("saves/slot/a.txt", "hello");
+        fs::appendText("saves/slot/a.txt", " world");
+        fs::readText("saves/slot/a.txt")"#,
+    );
+
+    assert_eq!(res, string("hello world"));
+    assert_eq!(fs::read_to_string(dir.join("saves/slot/a.txt")).unwrap(), "hello world");
+}
+
+#[test]
+fn paths_outside_of_the_sandbox_fail_with_an_io_error() {
+    let dir = temp_dir("paths_outside_of_the_sandbox_fail_with_an_io_error");
+
+    write_files(&dir, &[("inside/a.txt", "a"), ("secret.txt", "s")]);
+
+    let res = run_in(&dir.join("inside"), r#"fs::readText("../secret.txt").kind"#);
+
+    assert_eq!(res, string("outsideSandbox"));
+}
+
+#[test]
+fn missing_files_fail_with_not_found() {
+    let dir = temp_dir("missing_files_fail_with_not_found");
+
+    let res = run_in(&dir, r#"fs::readText("nope.txt").kind"#);
+
+    assert_eq!(res, string("notFound"));
+}
+
+#[test]
+fn list_dir_returns_sorted_names() {
+    let dir = temp_dir("list_dir_returns_sorted_names");
+
+    write_files(&dir, &[("b.txt", ""), ("a.txt", ""), ("c/d.txt", "")]);
+
+    let res = run_in(&dir, r#"use @std:str as s; s::join(fs::listDir("."), ",")"#);
+
+    assert_eq!(res, string("a.txt,b.txt,c"));
+}
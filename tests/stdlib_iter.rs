@@ -0,0 +1,91 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+
+#[test]
+fn sort_orders_numbers_with_nan_without_panicking() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        use @std:math as m;
+        gen fn items() -> num { yield 3; yield 0 / 0; yield 1; yield 2; }
+        it::sort(items())
+            ->> it::filter(::(x: num) -> bool { m::isFinite(x) })
+            ->> it::fold(0, ::(acc: num, x: num) -> num { acc * 10 + x })"#,
+    );
+
+    assert_eq!(res, num(123.0));
+}
+
+#[test]
+fn sort_survives_an_inconsistent_comparator() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        let items = it::sort(1..50, ::(a: num, b: num) -> num { 0 - 1 });
+        it::sum(items)"#,
+    );
+
+    assert_eq!(res, num(1225.0));
+}
+
+#[test]
+fn sort_uses_the_comparator_and_is_stable() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        use @std:str as s;
+        it::sort(s::split("bb,a,cc,d", ","), ::(a: str, b: str) -> num { s::len(a) - s::len(b) })
+            ->> it::fold("", ::(acc: str, x: str) -> str { acc + x })"#,
+    );
+
+    assert_eq!(res, string("adbbcc"));
+}
+
+#[test]
+fn zip_and_enumerate_keep_values_of_any_type() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        use @std:str as s;
+        let words = s::split("a,b", ",");
+        let pairs = it::zip(words, words)
+            ->> it::fold("", ::(acc: str, pair: indefinite) -> str { acc + pair.first + pair.second });
+        it::enumerate(words)
+            ->> it::fold(pairs, ::(acc: str, entry: indefinite) -> str { acc + entry.value })"#,
+    );
+
+    assert_eq!(res, string("aabbab"));
+}
+
+#[test]
+fn functions_chain_with_das() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        1..6 ->> it::map(::(x: num) -> num { x * 2 })
+            ->> it::filter(::(x: num) -> bool { x > 4 })
+            ->> it::reverse
+            ->> it::fold(0, ::(acc: num, x: num) -> num { acc * 100 + x })"#,
+    );
+
+    assert_eq!(res, num(100806.0));
+}
+
+#[test]
+fn huge_ranges_are_skipped_and_taken_lazily() {
+    let res = run_main(
+        r#"use @std:iter as it;
+        0..100000000 ->> it::skip(10) ->> it::take(3) ->> it::sum"#,
+    );
+
+    assert_eq!(res, num(33.0));
+}
+
+#[test]
+fn functions_with_wrong_argument_types_are_rejected() {
+    let message = error_of(
+        "use @std:iter as it;\nuse @std:str as s;",
+        r#"s::split("a,b", ",") ->> it::map(::(x: num) -> num { x })"#,
+    );
+
+    assert_eq!(
+        message,
+        "`map` cannot pass item 0 of type `str` to argument `x` of type `num`."
+    );
+}
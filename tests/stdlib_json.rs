@@ -0,0 +1,55 @@
+mod common;
+
+use common::{num, run_main, string};
+use mountain_sakura::prelude::*;
+
+fn json(expr: &str) -> RuntimeValue {
+    run_main(&format!("use @std:json as json;\n{}", expr))
+}
+
+#[test]
+fn parse_and_stringify_round_trip() {
+    let res = json(
+        r#"json::stringify(json::parse("{\"name\": \"Sakura\", \"tags\": [1, 2], \"ok\": true, \"none\": null}"))"#,
+    );
+
+    assert_eq!(
+        res,
+        string(r#"{"name":"Sakura","tags":[1,2],"ok":true,"none":null}"#)
+    );
+}
+
+#[test]
+fn objects_have_a_field_for_every_key() {
+    assert_eq!(
+        json(r#"json::parse("{\"name\": \"Sakura\"}").name"#),
+        string("Sakura")
+    );
+    assert_eq!(
+        json(r#"json::get(json::parse("{\"a\": 1}"), "b")"#),
+        RuntimeValue::Null
+    );
+}
+
+#[test]
+fn objects_are_changed_with_set_and_remove() {
+    let res = json(
+        r#"let o = json::object();
+        json::set(o, "a", 1);
+        json::set(o, "b", 2);
+        json::remove(o, "a");
+        json::stringify(o)"#,
+    );
+
+    assert_eq!(res, string(r#"{"b":2}"#));
+}
+
+#[test]
+fn malformed_json_returns_a_json_error() {
+    assert_eq!(
+        json(r#"typeof json::parse("{\"a\": }")"#),
+        string("JsonError")
+    );
+    assert_eq!(json("json::parse(\"[1,\n 2,]\").line"), num(2.0));
+    assert_eq!(json("json::parse(\"[1,\n 2,]\").column"), num(4.0));
+}
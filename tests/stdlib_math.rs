@@ -0,0 +1,40 @@
+mod common;
+
+use common::{error_of, num, run_main};
+use mountain_sakura::prelude::*;
+
+#[test]
+fn clamp_keeps_values_inside_of_the_bounds() {
+    let res = run_main(
+        r#"use @std:math as m;
+        m::clamp(5, 0, 3) + m::clamp(0 - 5, 0, 3) + m::clamp(2, 0, 3)"#,
+    );
+
+    assert_eq!(res, num(5.0));
+}
+
+#[test]
+fn clamp_rejects_nan_bounds() {
+    let setup = "use @std:math as m;";
+
+    assert!(error_of(setup, "m::clamp(1, 0 / 0, 3)").contains("NaN bound"));
+    assert!(error_of(setup, "m::clamp(1, 0, 0 / 0)").contains("NaN bound"));
+}
+
+#[test]
+fn math_functions_and_constants() {
+    let math = |expr: &str| run_main(&format!("use @std:math as m;\n{}", expr));
+
+    assert_eq!(math("m::round(m::sin(m::PI / 2))"), num(1.0));
+    assert_eq!(math("m::max(3, 9, 4) - m::min(3, 9, 4)"), num(6.0));
+    assert_eq!(math("m::idiv(7, 2) + m::fract(2.5)"), num(3.5));
+    assert_eq!(math("m::log(8, 2) * m::sign(0 - 4)"), num(-3.0));
+    assert_eq!(math("m::isNaN(m::NAN) == m::isInfinite(m::INFINITY)"), RuntimeValue::Bool(true));
+}
+
+#[test]
+fn math_functions_check_their_arguments() {
+    let message = error_of("use @std:math as m;", r#"m::sqrt("4")"#);
+
+    assert!(message.contains("must be a number"), "{}", message);
+}
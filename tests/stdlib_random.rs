@@ -0,0 +1,94 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+use mountain_sakura::prelude::*;
+
+fn run_seeded(seed: u64, source: &str) -> RuntimeValue {
+    let resolver =
+        MemoryResolver::new().add_module("main", format!("use @std:random as rnd;\n{}", source));
+
+    MoSaRunner::new("main")
+        .with_resolver(resolver)
+        .with_random_seed(seed)
+        .run()
+        .unwrap()
+}
+
+#[test]
+fn the_same_seed_gives_the_same_numbers() {
+    let script = r#"rnd::uuid() + " " + rnd::uuid()"#;
+
+    assert_eq!(run_seeded(42, script), run_seeded(42, script));
+    assert_ne!(run_seeded(42, script), run_seeded(43, script));
+}
+
+#[test]
+fn seeded_generators_do_not_use_the_default_one() {
+    let script = r#"let dice = rnd::seeded(7);
+        dice.uuid()"#;
+
+    assert_eq!(run_seeded(1, script), run_seeded(2, script));
+}
+
+#[test]
+fn integers_include_both_ends() {
+    let res = run_seeded(
+        3,
+        r#"let low = 0;
+        let high = 0;
+        let outside = 0;
+        for 0..200 {
+            let v = rnd::integer(1, 3);
+            if v == 1 { low = 1; }
+            if v == 3 { high = 1; }
+            if v < 1 { outside = 1; }
+            if v > 3 { outside = 1; }
+        }
+        let res = "missing an end";
+        if low == 1 { if high == 1 { res = "ok"; } }
+        if outside == 1 { res = "outside"; }
+        res"#,
+    );
+
+    assert_eq!(res, string("ok"));
+}
+
+#[test]
+fn shuffling_keeps_every_item() {
+    let res = run_seeded(
+        5,
+        r#"let sum = 0;
+        for rnd::shuffle(1..=10) { sum = sum + ^value; }
+        sum"#,
+    );
+
+    assert_eq!(res, num(55.0));
+}
+
+#[test]
+fn uuids_are_version_4() {
+    let uuid = |expr: &str| {
+        run_main(&format!(
+            "use @std:random as rnd;\nuse @std:str as s;\nlet id = rnd::uuid();\n{}",
+            expr
+        ))
+    };
+
+    assert_eq!(uuid("s::len(id)"), num(36.0));
+    assert_eq!(
+        uuid("s::substring(id, 8, 9) + s::substring(id, 13, 15) + s::substring(id, 18, 19) + s::substring(id, 23, 24)"),
+        string("--4--")
+    );
+}
+
+#[test]
+fn impossible_ranges_are_errors() {
+    assert_eq!(
+        error_of("use @std:random as rnd;", "rnd::integer(1.2, 1.8)"),
+        "`integer` has no whole numbers between 2 and 1."
+    );
+    assert_eq!(
+        error_of("use @std:random as rnd;", "rnd::range(2, 1)"),
+        "`range` got a minimum (2) bigger than the maximum (1)."
+    );
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+use mountain_sakura::prelude::*;
+
+fn regex(expr: &str) -> RuntimeValue {
+    run_main(&format!(
+        "use @std:regex as re;\nuse @std:str as s;\nlet date = re::compile(\"(?P<year>[0-9]{{4}})-(?P<month>[0-9]{{2}})-(?P<day>[0-9]{{2}})\");\n{}",
+        expr
+    ))
+}
+
+#[test]
+fn matches_have_their_position_and_groups() {
+    assert_eq!(regex(r#"date.find("due on 2024-02-29").start"#), num(7.0));
+    assert_eq!(
+        regex(r#"date.find("due on 2024-02-29").named.month"#),
+        string("02")
+    );
+    assert_eq!(
+        regex(r#"s::join(date.find("due on 2024-02-29").groups, ",")"#),
+        string("2024-02-29,2024,02,29")
+    );
+    assert_eq!(regex(r#"date.find("no date")"#), RuntimeValue::Null);
+}
+
+#[test]
+fn replacements_insert_groups_by_number_and_name() {
+    assert_eq!(
+        regex(r#"date.replace("2024-02-29 and 2025-01-02", "${day}/$2")"#),
+        string("29/02 and 02/01")
+    );
+    assert_eq!(
+        regex(r#"re::replaceFirst("a+", "caaat aa", "_")"#),
+        string("c_t aa")
+    );
+}
+
+#[test]
+fn patterns_can_be_passed_directly() {
+    assert_eq!(
+        regex(r#"s::join(re::split(",\s*", "a, b,c"), "|")"#),
+        string("a|b|c")
+    );
+    assert_eq!(
+        regex(r#"re::isMatch("^h", "hello")"#),
+        RuntimeValue::Bool(true)
+    );
+    assert_eq!(
+        regex(r#"re::replace(re::escape("1+1"), "1+1=2, 11", "two")"#),
+        string("two=2, 11")
+    );
+}
+
+#[test]
+fn invalid_patterns_are_reported() {
+    assert_ne!(regex(r#"re::validate("(")"#), RuntimeValue::Null);
+    assert_eq!(regex(r#"re::validate("[a-z]+")"#), RuntimeValue::Null);
+    assert!(error_of("use @std:regex as re;", r#"re::compile("(")"#)
+        .starts_with("`compile` got an invalid pattern `(`"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{error_of, num, run_main, string};
+use mountain_sakura::prelude::*;
+
+const SETUP: &str = "use @std:str as s;";
+
+#[test]
+fn repeat_and_pad_build_strings() {
+    let res = run_main(
+        r#"use @std:str as s;
+        s::repeatStr("ab", 2) + s::padStart("7", 3, "0") + s::padEnd("x", 2)"#,
+    );
+
+    assert_eq!(res, string("abab007x "));
+}
+
+#[test]
+fn repeat_and_pad_reject_huge_strings() {
+    for expr in [
+        r#"s::repeatStr("ab", 100000000000000000000)"#,
+        r#"s::repeatStr("ab", 1000000000)"#,
+        r#"s::padStart("a", 100000000000000000000)"#,
+        r#"s::padEnd("a", 1000000000, "🌸")"#,
+    ] {
+        assert!(
+            error_of(SETUP, expr).contains("cannot create a string longer than"),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn from_char_code_rejects_codes_that_are_too_big() {
+    assert_eq!(run_main(r#"use @std:str as s; s::fromCharCode(65)"#), string("A"));
+
+    // 2^32 + 65 used to be truncated to `A`
+    assert!(error_of(SETUP, "s::fromCharCode(4294967361)").contains("not a valid char code"));
+    assert!(error_of(SETUP, "s::fromCharCode(1114112)").contains("not a valid char code"));
+}
+
+#[test]
+fn indexes_count_characters() {
+    let str = |expr: &str| run_main(&format!("use @std:str as s;\n{}", expr));
+
+    assert_eq!(str(r#"s::len("🌸ab")"#), num(3.0));
+    assert_eq!(str(r#"s::substring("🌸abc", 1, 3)"#), string("ab"));
+    assert_eq!(str(r#"s::slice("hello", 0 - 3)"#), string("llo"));
+    assert_eq!(str(r#"s::find("a🌸b", "b")"#), num(2.0));
+    assert_eq!(str(r#"s::charCode("🌸")"#), num(127800.0));
+}
+
+#[test]
+fn splitting_joining_and_replacing() {
+    let str = |expr: &str| run_main(&format!("use @std:str as s;\n{}", expr));
+
+    assert_eq!(str(r#"s::join(s::split("a,b,c", ","), "-")"#), string("a-b-c"));
+    assert_eq!(str(r#"s::join(s::split("abc", ""))"#), string("abc"));
+    assert_eq!(str(r#"s::replaceFirst(s::replace("aXbXc", "X", "Y"), "Y", "Z")"#), string("aZbYc"));
+    assert_eq!(str(r#"s::upper(s::trim("  hi  "))"#), string("HI"));
+    assert_eq!(str(r#"s::parseNum("nope")"#), RuntimeValue::Null);
+}
+
+#[test]
+fn substring_outside_of_the_string_is_an_error() {
+    let message = error_of(SETUP, r#"s::substring("abc", 2, 9)"#);
+
+    assert!(message.contains("substring"), "{}", message);
+}
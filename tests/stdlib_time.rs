@@ -0,0 +1,79 @@
+mod common;
+
+use common::{num, string};
+use mountain_sakura::prelude::*;
+use std::time::Duration;
+
+fn run_with(clock: &FakeClock, source: &str) -> RuntimeValue {
+    let resolver =
+        MemoryResolver::new().add_module("main", format!("use @std:time as time;\n{}", source));
+
+    MoSaRunner::new("main")
+        .with_resolver(resolver)
+        .with_clock(clock.clone())
+        .run()
+        .unwrap()
+}
+
+#[test]
+fn sleeping_moves_a_fake_clock() {
+    let clock = FakeClock::new(0);
+
+    let res = run_with(
+        &clock,
+        r#"let start = time::now();
+        time::sleep(time::seconds(90));
+        time::elapsed(start).asMinutes()"#,
+    );
+
+    assert_eq!(res, num(1.5));
+    assert_eq!(run_with(&clock, "time::unixSeconds()"), num(90.0));
+}
+
+#[test]
+fn the_host_advances_a_fake_clock() {
+    let clock = FakeClock::new(1_000);
+    clock.advance(Duration::from_millis(500));
+
+    assert_eq!(run_with(&clock, "time::unixMillis()"), num(1_500.0));
+}
+
+#[test]
+fn durations_are_printed_by_unit() {
+    let clock = FakeClock::new(0);
+
+    let res = run_with(
+        &clock,
+        "time::hours(1).plus(time::minutes(2)).plus(time::millis(3500))",
+    );
+
+    assert_eq!(res.to_string(), "1h 2m 3.5s");
+}
+
+#[test]
+fn dates_are_formatted_and_parsed_as_iso() {
+    let clock = FakeClock::new(0);
+
+    assert_eq!(
+        run_with(
+            &clock,
+            "time::formatIso(time::fromDate(2024, 2, 29, 10, 30))"
+        ),
+        string("2024-02-29T10:30:00.000Z")
+    );
+    assert_eq!(
+        run_with(
+            &clock,
+            r#"time::formatIso(time::parseIso("2024-02-29T12:30:00+02:00"))"#
+        ),
+        string("2024-02-29T10:30:00.000Z")
+    );
+    assert_eq!(
+        run_with(&clock, r#"time::parseIso("2023-02-29T00:00:00Z")"#),
+        RuntimeValue::Null
+    );
+    assert_eq!(
+        run_with(&clock, "time::date(time::fromDate(2024, 2, 29)).weekday"),
+        num(4.0)
+    );
+}